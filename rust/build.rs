@@ -0,0 +1,36 @@
+// Builds the small C FFI shim around liblua, used by `rbf::map::ScriptEngine` when the
+// `lua` Cargo feature is enabled. Skipped entirely otherwise, so the default build never
+// needs a Lua toolchain or a C compiler.
+use std::env;
+use std::process::Command;
+
+fn main() {
+    // nothing to do unless the `lua` feature was requested
+    if env::var_os("CARGO_FEATURE_LUA").is_none() {
+        return;
+    }
+
+    let lua_dir = format!("{}/src/lua", env::current_dir().unwrap().display());
+
+    let makefile = if cfg!(target_os = "linux") {
+        "src/lua/luacall_linux.mak"
+    } else if cfg!(target_os = "macos") {
+        "src/lua/luacall_macos.mak"
+    } else if cfg!(target_os = "windows") {
+        "src/lua/luacall_windows.mak"
+    } else {
+        panic!("the `lua` feature is not supported on this target OS");
+    };
+
+    let make = if cfg!(target_os = "windows") { "nmake" } else { "make" };
+
+    Command::new(make)
+        .env("LUA_DIR", &lua_dir)
+        .args(&["-f", makefile])
+        .status()
+        .expect("failed to build the luacall shim");
+
+    println!("cargo:rustc-link-lib=static=lua");
+    println!("cargo:rustc-link-lib=static=luacall");
+    println!("cargo:rustc-link-search={}", lua_dir);
+}
@@ -1,10 +1,64 @@
 use getopts::{Fail, Matches, Options};
+use std::convert::TryFrom;
+use std::fmt;
+use std::path::PathBuf;
 use std::str::FromStr;
 
+use rbf::filter::recordfilter::RecordFilter;
 use rbf::reader::ReaderLazyness;
 
+/// Errors that can occur while parsing the command line. `CommandLineArguments::from_args`
+/// never panics or exits, and only writes to stdout to print conflicting-option warnings
+/// when `RBF_STRICT` is unset: callers decide what to do with the error (the `urr` binary
+/// prints it and exits via the `error_check!` macro).
+#[derive(Debug)]
+pub enum CliError {
+    /// an option was present but getopts couldn't extract its argument
+    MissingArgument(&'static str),
+
+    /// an option argument couldn't be converted to the expected type
+    BadConversion { opt: &'static str, value: String },
+
+    /// the `--rf` record filter expression couldn't be parsed
+    BadFilter(String),
+
+    /// getopts rejected the command line itself (unknown option, missing required option, ...)
+    GetoptsFail(Fail),
+
+    /// `RBF_STRICT` is set and two or more options that don't make sense together were given
+    ConflictingOptions(String),
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CliError::MissingArgument(opt) => {
+                write!(f, "option <{}> requires an argument", opt)
+            }
+            CliError::BadConversion { opt, value } => write!(
+                f,
+                "option argument <{}> for option <{}> provided, but conversion failed",
+                value, opt
+            ),
+            CliError::BadFilter(expr) => {
+                write!(f, "invalid record filter expression <{}>", expr)
+            }
+            CliError::GetoptsFail(e) => write!(f, "{}", e),
+            CliError::ConflictingOptions(msg) => {
+                write!(f, "conflicting options (RBF_STRICT is set): {}", msg)
+            }
+        }
+    }
+}
+
+impl From<Fail> for CliError {
+    fn from(e: Fail) -> CliError {
+        CliError::GetoptsFail(e)
+    }
+}
+
 // help text
-static HELP_MSG: &'static str = r#"
+pub static HELP_MSG: &'static str = r#"
 NAME
        urr - read a record-based file and convert it to a known format
 
@@ -17,6 +71,10 @@ DESCRIPTION
 OPTIONS
        -b : don't write output file but just read input file (benchmark).
 
+       --csv-delim char : field delimiter used by the "csv" output format (default ';').
+
+       --ascii : treat the input file as ASCII (default).
+
        --check : check whether field patterns are matched.
 
        --debug : additional verbosity.       
@@ -33,10 +91,21 @@ OPTIONS
 
        -l layout : name of the input file layout.
 
+       --lua-script file : Lua script defining the --lua-filter and/or --lua-transform functions.
+
+       --lua-filter func : name of a Lua function called with the raw record line; the record is
+                            discarded when it returns an empty string or "0".
+
+       --lua-transform func : name of a Lua function called with the value of --lua-transform-field,
+                               the returned string replaces the field value.
+
+       --lua-transform-field field : field name whose value is passed through --lua-transform.
+
        -o format : name of the output format. Possible values are: 
 
         text:     an Ascii file, one line per record with field names (this is the default output format)
-        csv:      a text file, one record per line, fields separated by the ';'
+        csv:      one RFC 4180-quoted CSV file per record type, fields separated by ';' (see --csv-delim)
+        ndjson:   a newline-delimited JSON file, one JSON object per record
         ident:    same file format than the input file, but matching input parameters
         sqlite3:  a sqlite3 database file, one table per record
         tag:      a text file, one record per line, all fields tagged with the following format: field_name = "field_value"
@@ -44,7 +113,13 @@ OPTIONS
 
        -p : print out progress bar.
 
-       --rf fields : list of field regexes to filter out records.
+       --rf expr : boolean filter expression to select records, e.g. "AMOUNT > 100 AND (STATUS = OK OR STATUS ~ ^WARN)".
+              Supported operators: = != ~ !~ < <= > >= <>, combined with AND/OR/NOT (or the
+              symbolic && / || / !) and parentheses.
+              <> tests inclusive range membership, e.g. "EVENT_TS <> 2020-01-01..2020-12-31"
+              (an empty bound, as in "..2020-12-31" or "2020-01-01..", is unbounded on that side).
+              A single bare condition (e.g. "STATUS = OK") also works, and "f1 = a; f2 = b" is
+              sugar for "f1 = a AND f2 = b", kept for older filter expressions.
 
        --raw : use raw values instead of blank stripped values
 
@@ -52,9 +127,13 @@ OPTIONS
 
        --strict: if a record if not found is the layout, exit the program.
 
-       -v : print out options (verbose).
+       --utf8 : treat the input file as UTF-8.
 
+       -v : print out options (verbose).
 
+ENVIRONMENT
+       RBF_STRICT : when set, option combinations that don't make sense together
+                    (e.g. --ascii with --utf8) are hard errors instead of warnings.
 
 "#;
 
@@ -83,11 +162,16 @@ const OPTION_ASCII_MODE: OptionLongShort = ("", "ascii", "");
 const OPTION_UTF8_MODE: OptionLongShort = ("", "utf8", "");
 const OPTION_SKIP_FIELDS: OptionLongShort = ("", "skip", "FIELDS");
 const OPTION_IGNORE_LINE: OptionLongShort = ("", "ignore", "REGEX");
+const OPTION_LUA_SCRIPT: OptionLongShort = ("", "lua-script", "SCRIPT");
+const OPTION_LUA_FILTER_FUNC: OptionLongShort = ("", "lua-filter", "FUNC");
+const OPTION_LUA_TRANSFORM_FUNC: OptionLongShort = ("", "lua-transform", "FUNC");
+const OPTION_LUA_TRANSFORM_FIELD: OptionLongShort = ("", "lua-transform-field", "FIELD");
+const OPTION_CSV_DELIMITER: OptionLongShort = ("", "csv-delim", "CHAR");
 
 #[derive(Debug)]
 pub struct CommandLineArguments {
-    pub input_file: String,
-    pub layout_file: String,
+    pub input_file: PathBuf,
+    pub layout_file: PathBuf,
     pub only_read: bool,
     pub progress_bar: bool,
     pub verbose: bool,
@@ -95,12 +179,19 @@ pub struct CommandLineArguments {
     pub sample_size: Option<u64>,
     pub reader_mode: ReaderLazyness,
     pub check_pattern: bool,
+    pub ascii_mode: bool,
+    pub utf8_mode: bool,
     pub filter_list: Option<String>,
-    pub filter_file: Option<String>,
+    pub filter_file: Option<PathBuf>,
     pub skip_fields: Option<String>,
     pub ignore_lines: Option<String>,
     pub output_format: Option<String>,
     pub record_filter_list: Option<String>,
+    pub lua_script: Option<String>,
+    pub lua_filter_func: Option<String>,
+    pub lua_transform_func: Option<String>,
+    pub lua_transform_field: Option<String>,
+    pub csv_delimiter: Option<char>,
 }
 
 impl CommandLineArguments {
@@ -119,39 +210,53 @@ impl CommandLineArguments {
     }
 
     /// Extracts the optional argument if option is provided.
-    fn extract_optional_arg<T: FromStr>(matches: &Matches, opt: &'static str) -> Option<T> {
+    fn extract_optional_arg<T: FromStr>(
+        matches: &Matches,
+        opt: &'static str,
+    ) -> Result<Option<T>, CliError> {
         // option not provided?
         if !matches.opt_present(opt) {
-            return None;
+            return Ok(None);
         }
 
         // now something to process
-        let arg = match matches.opt_str(opt) {
-            Some(v) => v,
-            None => panic!("fatal: option <{}> provided but no argument", opt),
-        };
+        let arg = matches
+            .opt_str(opt)
+            .ok_or(CliError::MissingArgument(opt))?;
 
         // now try to convert argument
-        let conv = match arg.parse::<T>() {
-            Ok(v) => v,
-            Err(_) => panic!(
-                "fatal: option argument <{}> for option <{}> provided, but conversion failed!",
-                arg, opt
-            ),
+        let conv = arg.parse::<T>().map_err(|_| CliError::BadConversion {
+            opt,
+            value: arg.clone(),
+        })?;
+
+        Ok(Some(conv))
+    }
+
+    /// Extracts the `--rf` argument, making sure it parses as a `RecordFilter` before
+    /// it's handed back.
+    fn extract_record_filter_arg(
+        matches: &Matches,
+        opt: &'static str,
+    ) -> Result<Option<String>, CliError> {
+        let arg = match CommandLineArguments::extract_optional_arg::<String>(matches, opt)? {
+            Some(v) => v,
+            None => return Ok(None),
         };
 
-        Some(conv)
+        match RecordFilter::try_from(arg.as_str()) {
+            Ok(_) => Ok(Some(arg)),
+            Err(_) => Err(CliError::BadFilter(arg)),
+        }
     }
 
-    pub fn from_args(args: &Vec<String>) -> Result<CommandLineArguments, Fail> {
-        // get arguments
-        if args.len() == 1
+    /// True when the user asked for help, i.e. no arguments at all or a bare `-h`/`--help`.
+    pub fn wants_help(args: &[String]) -> bool {
+        args.len() == 1
             || (args.len() == 2 && (args[1] == OPTION_HELP.0 || args[1] == OPTION_HELP.1))
-        {
-            println!("{}", HELP_MSG);
-            ::std::process::exit(1);
-        }
+    }
 
+    pub fn from_args(args: &Vec<String>) -> Result<CommandLineArguments, CliError> {
         // define our new set of options
         let mut opts = Options::new();
 
@@ -170,30 +275,34 @@ impl CommandLineArguments {
         CommandLineArguments::set_optional_opt(&mut opts, &OPTION_FIELD_FILTER_LIST);
         CommandLineArguments::set_optional_opt(&mut opts, &OPTION_FIELD_FILTER_FILE);
         CommandLineArguments::set_optional_opt(&mut opts, &OPTION_CHECK_PATTERN);
+        CommandLineArguments::set_optional_opt(&mut opts, &OPTION_ASCII_MODE);
+        CommandLineArguments::set_optional_opt(&mut opts, &OPTION_UTF8_MODE);
         CommandLineArguments::set_optional_opt(&mut opts, &OPTION_SKIP_FIELDS);
         CommandLineArguments::set_optional_opt(&mut opts, &OPTION_IGNORE_LINE);
         CommandLineArguments::set_optional_opt(&mut opts, &OPTION_OUTPUT_FORMAT);
         CommandLineArguments::set_optional_opt(&mut opts, &OPTION_RECORD_FILTER_LIST);
+        CommandLineArguments::set_optional_opt(&mut opts, &OPTION_LUA_SCRIPT);
+        CommandLineArguments::set_optional_opt(&mut opts, &OPTION_LUA_FILTER_FUNC);
+        CommandLineArguments::set_optional_opt(&mut opts, &OPTION_LUA_TRANSFORM_FUNC);
+        CommandLineArguments::set_optional_opt(&mut opts, &OPTION_LUA_TRANSFORM_FIELD);
+        CommandLineArguments::set_optional_opt(&mut opts, &OPTION_CSV_DELIMITER);
 
         // process arguments
         let matches = opts.parse(&args[1..])?;
 
-        // check lazyness
-        let mut reader_mode = if matches.opt_present(OPTION_STRICT_MODE.0) {
+        // check lazyness: strict only applies when asked for and not overridden by --lazy
+        let reader_mode = if matches.opt_present(OPTION_STRICT_MODE.0)
+            && !matches.opt_present(OPTION_LAZY_MODE.0)
+        {
             ReaderLazyness::Strict
         } else {
             ReaderLazyness::Lazy
         };
-        reader_mode = if matches.opt_present(OPTION_LAZY_MODE.0) {
-            ReaderLazyness::Lazy
-        } else {
-            ReaderLazyness::Strict
-        };
 
         // save arguments to struct
-        Ok(CommandLineArguments {
-            input_file: matches.opt_str(OPTION_INPUT_FILE.0).unwrap(),
-            layout_file: matches.opt_str(OPTION_LAYOUT_FILE.0).unwrap(),
+        let cmd_args = CommandLineArguments {
+            input_file: PathBuf::from(matches.opt_str(OPTION_INPUT_FILE.0).unwrap()),
+            layout_file: PathBuf::from(matches.opt_str(OPTION_LAYOUT_FILE.0).unwrap()),
             only_read: matches.opt_present(OPTION_BENCHMARK.0),
             progress_bar: matches.opt_present(OPTION_PROGRESS_BAR.0),
             verbose: matches.opt_present(OPTION_VERBOSE.0),
@@ -201,33 +310,95 @@ impl CommandLineArguments {
             sample_size: CommandLineArguments::extract_optional_arg::<u64>(
                 &matches,
                 OPTION_SAMPLE.0,
-            ),
+            )?,
             reader_mode: reader_mode,
             check_pattern: matches.opt_present(OPTION_CHECK_PATTERN.0),
+            ascii_mode: matches.opt_present(OPTION_ASCII_MODE.1),
+            utf8_mode: matches.opt_present(OPTION_UTF8_MODE.1),
             filter_list: CommandLineArguments::extract_optional_arg::<String>(
                 &matches,
                 OPTION_FIELD_FILTER_LIST.1,
-            ),
-            filter_file: CommandLineArguments::extract_optional_arg::<String>(
+            )?,
+            filter_file: CommandLineArguments::extract_optional_arg::<PathBuf>(
                 &matches,
                 OPTION_FIELD_FILTER_FILE.1,
-            ),
+            )?,
             skip_fields: CommandLineArguments::extract_optional_arg::<String>(
                 &matches,
                 OPTION_SKIP_FIELDS.1,
-            ),
+            )?,
             ignore_lines: CommandLineArguments::extract_optional_arg::<String>(
                 &matches,
                 OPTION_IGNORE_LINE.1,
-            ),
+            )?,
             output_format: CommandLineArguments::extract_optional_arg::<String>(
                 &matches,
                 OPTION_OUTPUT_FORMAT.1,
-            ),
-            record_filter_list: CommandLineArguments::extract_optional_arg::<String>(
+            )?,
+            record_filter_list: CommandLineArguments::extract_record_filter_arg(
                 &matches,
                 OPTION_RECORD_FILTER_LIST.1,
-            ),
-        })
+            )?,
+            lua_script: CommandLineArguments::extract_optional_arg::<String>(
+                &matches,
+                OPTION_LUA_SCRIPT.1,
+            )?,
+            lua_filter_func: CommandLineArguments::extract_optional_arg::<String>(
+                &matches,
+                OPTION_LUA_FILTER_FUNC.1,
+            )?,
+            lua_transform_func: CommandLineArguments::extract_optional_arg::<String>(
+                &matches,
+                OPTION_LUA_TRANSFORM_FUNC.1,
+            )?,
+            lua_transform_field: CommandLineArguments::extract_optional_arg::<String>(
+                &matches,
+                OPTION_LUA_TRANSFORM_FIELD.1,
+            )?,
+            csv_delimiter: CommandLineArguments::extract_optional_arg::<char>(
+                &matches,
+                OPTION_CSV_DELIMITER.1,
+            )?,
+        };
+
+        // pedantic cross-option checks: hard errors under RBF_STRICT, warnings otherwise
+        let conflicts = CommandLineArguments::check_conflicting_options(&cmd_args);
+        if !conflicts.is_empty() {
+            if ::std::env::var("RBF_STRICT").is_ok() {
+                return Err(CliError::ConflictingOptions(conflicts.join("; ")));
+            }
+            for c in &conflicts {
+                println!("warning: {}", c);
+            }
+        }
+
+        Ok(cmd_args)
+    }
+
+    /// Flags option combinations that are mutually exclusive or redundant: `--ascii`
+    /// together with `--utf8`, `-b` (read-only) combined with `-o`/`--output` (output
+    /// requested but never written), or `--fl` and `--ff` given simultaneously. These
+    /// stay warnings unless `RBF_STRICT` is set (see `from_args`).
+    fn check_conflicting_options(cmd: &CommandLineArguments) -> Vec<String> {
+        let mut conflicts = Vec::new();
+
+        if cmd.ascii_mode && cmd.utf8_mode {
+            conflicts.push("--ascii and --utf8 are mutually exclusive".to_string());
+        }
+
+        if cmd.only_read && cmd.output_format.is_some() {
+            conflicts.push(
+                "-b (read-only) is redundant with -o/--output: output would never be written"
+                    .to_string(),
+            );
+        }
+
+        if cmd.filter_list.is_some() && cmd.filter_file.is_some() {
+            conflicts.push(
+                "--fl and --ff are redundant: only one field filter source is used".to_string(),
+            );
+        }
+
+        conflicts
     }
 }
@@ -11,6 +11,8 @@ use regex::Regex;
 extern crate rbf;
 use rbf::filter::recordfilter::RecordFilter;
 use rbf::layout::Layout;
+#[cfg(feature = "lua")]
+use rbf::map::ScriptEngine;
 use rbf::reader::{Reader, ReaderLazyness};
 use rbf::record::AsciiMode;
 
@@ -32,6 +34,11 @@ fn main() {
     // get arguments
     let args: Vec<String> = env::args().collect();
 
+    if CommandLineArguments::wants_help(&args) {
+        println!("{}", args::HELP_MSG);
+        ::std::process::exit(1);
+    }
+
     // process arguments
     let mut cmd_args = error_check!(CommandLineArguments::from_args(&args));
     if cmd_args.debug {
@@ -57,17 +64,17 @@ fn main() {
         }
     }
 
-    // in this case, the list of field regexes to filter out records
-    let mut record_filter = RecordFilter { expr: Vec::new() };
+    // in this case, the boolean filter expression used to filter out records
+    let mut record_filter = RecordFilter { expr: None };
     let record_filter_list: String;
 
     if let Some(record_filter_list) = cmd_args.record_filter_list.clone() {
         record_filter = RecordFilter::from(&*record_filter_list);
 
         // check if field names are valid
-        for f in &record_filter.expr {
-            if !layout.contains_field(&f.fname) {
-                panic!("field name {} not found in layout!", f.fname);
+        for fname in record_filter.field_names() {
+            if !layout.contains_field(&fname) {
+                panic!("field name {} not found in layout!", fname);
             }
         }
 
@@ -128,14 +135,26 @@ fn main() {
     if cmd_args.verbose {
         println!(
             "info: input file is <{}>, size: {} bytes",
-            &cmd_args.input_file, reader.file_size
+            cmd_args.input_file.display(),
+            reader.file_size
         );
         println!(
             "info: layout file is <{}> with {} record(s)",
-            &cmd_args.layout_file, nb_records_created
+            cmd_args.layout_file.display(),
+            nb_records_created
         );
     }
 
+    // optional Lua engine, loaded once if a script was given on the command line
+    #[cfg(feature = "lua")]
+    let lua_engine = cmd_args.lua_script.as_ref().map(|script| ScriptEngine::new(script));
+    #[cfg(not(feature = "lua"))]
+    {
+        if cmd_args.lua_script.is_some() {
+            println!("warning: --lua-script was given but this binary was built without the `lua` feature, ignoring");
+        }
+    }
+
     // now create writer according to requested output format
     let output_format = match cmd_args.output_format {
         Some(v) => v,
@@ -143,7 +162,8 @@ fn main() {
     };
 
     // build output file name depending on format
-    let mut writer = create_writer(&output_format, &cmd_args.input_file);
+    let csv_delimiter = cmd_args.csv_delimiter.unwrap_or(';');
+    let mut writer = create_writer(&output_format, &cmd_args.input_file, csv_delimiter);
 
     // loop through records
     while let Some((stats, rec)) = reader.next() {
@@ -169,6 +189,31 @@ fn main() {
             continue;
         }
 
+        // Lua-driven record filter: discard the record before it's ever written out
+        #[cfg(feature = "lua")]
+        {
+            if let (Some(engine), Some(func)) = (&lua_engine, &cmd_args.lua_filter_func) {
+                if !engine.filter_record(func, &rec.value()) {
+                    continue;
+                }
+            }
+        }
+
+        // Lua-driven field transform: rewrite one field's value in place
+        #[cfg(feature = "lua")]
+        {
+            if let (Some(engine), Some(func), Some(fname)) = (
+                &lua_engine,
+                &cmd_args.lua_transform_func,
+                &cmd_args.lua_transform_field,
+            ) {
+                if rec.contains_field(fname) {
+                    let transformed = engine.transform_field(func, rec.get_value(fname));
+                    rec[rec.get(fname).unwrap()[0].index].set_value(&transformed);
+                }
+            }
+        }
+
         // check pattern?
         if cmd_args.check_pattern {
             for f in &*rec {
@@ -1,39 +1,85 @@
-use std::error::Error;
+use std::collections::HashMap;
 use std::fs::File;
 use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
 
 use crate::writer::writer::Writer;
-use rbf::record::{AsciiMode, Record, UTF8Mode};
+use rbf::record::Record;
 
+/// Writes one CSV file per record type, RFC 4180 quoting, with a configurable
+/// delimiter. Files are created lazily, the first time a given record name is seen
+/// (mirrors `SqliteWriter::ensure_table`'s lazy-create-by-name pattern).
 pub struct CsvWriter {
-    buffer: BufWriter<File>,
+    input_file: PathBuf,
+    delimiter: char,
+    files: HashMap<String, BufWriter<File>>,
 }
 
-impl Writer for CsvWriter {
-    fn new(input_file: &str) -> CsvWriter {
-        // build output file name
-        let output_file = input_file.to_owned() + ".csv";
+/// Quotes `value` per RFC 4180 when it contains the delimiter, a double quote, or a
+/// line break; embedded double quotes are doubled.
+fn quote_field(value: &str, delimiter: char) -> String {
+    if value.contains(delimiter) || value.contains('"') || value.contains('\n') || value.contains('\r')
+    {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+impl CsvWriter {
+    /// Same as `new`, but with a caller-chosen field delimiter instead of `;`.
+    pub fn with_delimiter(input_file: &Path, delimiter: char) -> CsvWriter {
+        CsvWriter {
+            input_file: input_file.to_path_buf(),
+            delimiter,
+            files: HashMap::new(),
+        }
+    }
+
+    /// `<input_file's parent>/<input_file's stem>_<rec_name>.csv`.
+    fn output_file_for(&self, rec_name: &str) -> PathBuf {
+        let stem = self
+            .input_file
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("output");
+        let dir = self.input_file.parent().unwrap_or_else(|| Path::new(""));
 
-        // open file for reading
-        let file = match File::create(&output_file) {
-            // if ok, create a new BufReader to read the file line by line
-            Ok(f) => f,
-            // The `description` method of `io::Error` returns a string that
-            // describes the error
-            Err(why) => panic!("couldn't open {}: {}", output_file, why.description()),
-        };
+        dir.join(format!("{}_{}.csv", stem, rec_name))
+    }
 
-        let buffer = BufWriter::new(file);
+    /// Returns the file for `rec_name`, creating it the first time this record
+    /// type is seen.
+    fn file_for(&mut self, rec_name: &str) -> &mut BufWriter<File> {
+        if !self.files.contains_key(rec_name) {
+            let output_file = self.output_file_for(rec_name);
+            let file = File::create(&output_file)
+                .unwrap_or_else(|why| panic!("couldn't open {}: {}", output_file.display(), why));
+            self.files.insert(rec_name.to_string(), BufWriter::new(file));
+        }
 
-        CsvWriter { buffer: buffer }
+        self.files.get_mut(rec_name).unwrap()
+    }
+}
+
+impl<M> Writer<M> for CsvWriter {
+    fn new(input_file: &Path) -> CsvWriter {
+        CsvWriter::with_delimiter(input_file, ';')
     }
 
     fn close(&self) {}
 
     #[allow(unused_must_use)]
-    fn write(&mut self, rec: &Record<AsciiMode>) {
-        let line: Vec<_> = rec.flist.iter().map(|f| f.value().to_string()).collect();
-        self.buffer.write(&line.join(";").as_bytes());
-        self.buffer.write(b"\n");
+    fn write(&mut self, rec: &Record<M>) {
+        let delimiter = self.delimiter;
+        let line: Vec<_> = rec
+            .flist
+            .iter()
+            .map(|f| quote_field(f.value(), delimiter))
+            .collect();
+
+        let buffer = self.file_for(&rec.name);
+        buffer.write(line.join(&delimiter.to_string()).as_bytes());
+        buffer.write(b"\n");
     }
 }
@@ -0,0 +1,38 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use crate::writer::writer::{append_extension, Writer};
+use rbf::record::Record;
+
+/// Writes each record back out as a fixed-width line, field values in place of the
+/// original raw substrings: this mirrors the input file layout (the `ident` output format).
+pub struct FixedWidthWriter {
+    buffer: BufWriter<File>,
+}
+
+impl<M> Writer<M> for FixedWidthWriter {
+    fn new(input_file: &Path) -> FixedWidthWriter {
+        // build output file name
+        let output_file = append_extension(input_file, ".fix");
+
+        // create the output file for writing
+        let file = match File::create(&output_file) {
+            // if ok, it gets wrapped in a BufWriter below
+            Ok(f) => f,
+            Err(why) => panic!("couldn't open {}: {}", output_file.display(), why),
+        };
+
+        let buffer = BufWriter::new(file);
+
+        FixedWidthWriter { buffer: buffer }
+    }
+
+    fn close(&self) {}
+
+    #[allow(unused_must_use)]
+    fn write(&mut self, rec: &Record<M>) {
+        self.buffer.write(rec.to_line().as_bytes());
+        self.buffer.write(b"\n");
+    }
+}
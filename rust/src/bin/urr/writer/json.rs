@@ -0,0 +1,73 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use crate::writer::writer::{append_extension, Writer};
+use rbf::record::Record;
+
+/// Writes one JSON object per line (newline-delimited JSON), each field keyed by both
+/// its `id` and its `name`, so a downstream consumer can pick whichever it already
+/// knows without re-running the fixed-width parse.
+pub struct JsonWriter {
+    buffer: BufWriter<File>,
+}
+
+/// Escapes the characters JSON forbids inside a string literal.
+fn json_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+impl<M> Writer<M> for JsonWriter {
+    fn new(input_file: &Path) -> JsonWriter {
+        // build output file name
+        let output_file = append_extension(input_file, ".json");
+
+        // create the output file for writing
+        let file = match File::create(&output_file) {
+            // if ok, it gets wrapped in a BufWriter below
+            Ok(f) => f,
+            Err(why) => panic!("couldn't open {}: {}", output_file.display(), why),
+        };
+
+        let buffer = BufWriter::new(file);
+
+        JsonWriter { buffer: buffer }
+    }
+
+    fn close(&self) {}
+
+    #[allow(unused_must_use)]
+    fn write(&mut self, rec: &Record<M>) {
+        let fields: Vec<_> = rec
+            .flist
+            .iter()
+            .map(|f| {
+                format!(
+                    "\"id\":\"{}\",\"name\":\"{}\",\"value\":\"{}\"",
+                    json_escape(&f.id),
+                    json_escape(&f.name),
+                    json_escape(f.value())
+                )
+            })
+            .collect();
+
+        self.buffer.write(b"{\"_record\":\"");
+        self.buffer.write(json_escape(&rec.name).as_bytes());
+        self.buffer.write(b"\",\"fields\":[");
+        let objects: Vec<_> = fields.iter().map(|f| format!("{{{}}}", f)).collect();
+        self.buffer.write(objects.join(",").as_bytes());
+        self.buffer.write(b"]}\n");
+    }
+}
@@ -0,0 +1,55 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use crate::writer::writer::{append_extension, Writer};
+use rbf::record::Record;
+
+/// Writes records as a GitHub-flavored markdown table, with a fresh header
+/// whenever the record type changes.
+pub struct MarkdownWriter {
+    last_recname: String,
+    buffer: BufWriter<File>,
+}
+
+impl<M> Writer<M> for MarkdownWriter {
+    fn new(input_file: &Path) -> MarkdownWriter {
+        // build output file name
+        let output_file = append_extension(input_file, ".md");
+
+        // create the output file for writing
+        let file = match File::create(&output_file) {
+            // if ok, it gets wrapped in a BufWriter below
+            Ok(f) => f,
+            Err(why) => panic!("couldn't open {}: {}", output_file.display(), why),
+        };
+
+        MarkdownWriter {
+            last_recname: String::new(),
+            buffer: BufWriter::new(file),
+        }
+    }
+
+    fn close(&self) {}
+
+    #[allow(unused_must_use)]
+    fn write(&mut self, rec: &Record<M>) {
+        // build header from field names only if not the same record than before
+        if self.last_recname != rec.name {
+            self.buffer.write(b"\n");
+
+            let header: Vec<_> = rec.flist.iter().map(|f| f.name.clone()).collect();
+            self.buffer.write(format!("| {} |\n", header.join(" | ")).as_bytes());
+
+            let sep: Vec<_> = rec.flist.iter().map(|_| "---").collect();
+            self.buffer.write(format!("| {} |\n", sep.join(" | ")).as_bytes());
+
+            // last rec name is now current
+            self.last_recname = rec.name.clone();
+        }
+
+        // now data
+        let data: Vec<_> = rec.flist.iter().map(|f| f.value().to_string()).collect();
+        self.buffer.write(format!("| {} |\n", data.join(" | ")).as_bytes());
+    }
+}
@@ -0,0 +1,9 @@
+pub mod writer;
+
+pub mod csv;
+pub mod fixedwidth;
+pub mod json;
+pub mod markdown;
+pub mod ndjson;
+pub mod sqlite;
+pub mod text;
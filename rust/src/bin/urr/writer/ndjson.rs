@@ -0,0 +1,71 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use crate::writer::writer::{append_extension, Writer};
+use rbf::record::Record;
+
+/// Writes one JSON object per line (newline-delimited JSON), each field's value typed
+/// (number, string, ...) per its declared `BaseType` rather than always a JSON string.
+pub struct NdjsonWriter {
+    buffer: BufWriter<File>,
+}
+
+/// Escapes the characters JSON forbids inside a string literal.
+fn json_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+impl<M> Writer<M> for NdjsonWriter {
+    fn new(input_file: &Path) -> NdjsonWriter {
+        // build output file name
+        let output_file = append_extension(input_file, ".ndjson");
+
+        // create the output file for writing
+        let file = match File::create(&output_file) {
+            // if ok, it gets wrapped in a BufWriter below
+            Ok(f) => f,
+            Err(why) => panic!("couldn't open {}: {}", output_file.display(), why),
+        };
+
+        let buffer = BufWriter::new(file);
+
+        NdjsonWriter { buffer: buffer }
+    }
+
+    fn close(&self) {}
+
+    #[allow(unused_must_use)]
+    fn write(&mut self, rec: &Record<M>) {
+        let fields: Vec<_> = rec
+            .flist
+            .iter()
+            .map(|f| {
+                let value = f
+                    .ftype
+                    .base_type
+                    .to_json(f.value())
+                    .unwrap_or_else(|e| panic!("unable to convert field {} to JSON: {}", f.name, e));
+                format!("\"{}\":{}", json_escape(&f.name), value)
+            })
+            .collect();
+
+        self.buffer.write(b"{\"_record\":\"");
+        self.buffer.write(json_escape(&rec.name).as_bytes());
+        self.buffer.write(b"\",");
+        self.buffer.write(fields.join(",").as_bytes());
+        self.buffer.write(b"}\n");
+    }
+}
@@ -0,0 +1,90 @@
+use std::collections::HashSet;
+use std::path::Path;
+
+use rusqlite::Connection;
+
+use crate::writer::writer::{append_extension, Writer};
+use rbf::record::Record;
+
+/// Writes each record into a sqlite3 database file, one table per record name. Tables
+/// are created lazily, the first time a given record name is seen.
+pub struct SqliteWriter {
+    conn: Connection,
+    known_tables: HashSet<String>,
+}
+
+/// Record/field names become SQL identifiers spliced straight into DDL/DML strings,
+/// which can't be parameterized like values can; reject anything that isn't a plain
+/// identifier so a layout name can't break out of its quotes.
+fn quote_identifier(name: &str) -> String {
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        panic!(
+            "layout name \"{}\" is not a valid SQL identifier (expected [A-Za-z0-9_]+)",
+            name
+        );
+    }
+    format!("\"{}\"", name)
+}
+
+impl SqliteWriter {
+    /// Creates the table for `rec` if it hasn't been seen yet, one `TEXT` column per field.
+    fn ensure_table<M>(&mut self, rec: &Record<M>) {
+        if self.known_tables.contains(&rec.name) {
+            return;
+        }
+
+        let columns: Vec<_> = rec
+            .flist
+            .iter()
+            .map(|f| format!("{} TEXT", quote_identifier(&f.name)))
+            .collect();
+        let ddl = format!(
+            "CREATE TABLE IF NOT EXISTS {} ({})",
+            quote_identifier(&rec.name),
+            columns.join(",")
+        );
+        self.conn
+            .execute(&ddl, rusqlite::NO_PARAMS)
+            .unwrap_or_else(|e| panic!("unable to create table {}: {}", rec.name, e));
+
+        self.known_tables.insert(rec.name.clone());
+    }
+}
+
+impl<M> Writer<M> for SqliteWriter {
+    fn new(input_file: &Path) -> SqliteWriter {
+        // build output file name
+        let output_file = append_extension(input_file, ".db");
+
+        let conn = match Connection::open(&output_file) {
+            Ok(c) => c,
+            Err(why) => panic!("couldn't open {}: {}", output_file.display(), why),
+        };
+
+        SqliteWriter {
+            conn: conn,
+            known_tables: HashSet::new(),
+        }
+    }
+
+    fn close(&self) {}
+
+    fn write(&mut self, rec: &Record<M>) {
+        self.ensure_table(rec);
+
+        let placeholders: Vec<_> = rec.flist.iter().map(|_| "?").collect();
+        let sql = format!(
+            "INSERT INTO {} VALUES ({})",
+            quote_identifier(&rec.name),
+            placeholders.join(",")
+        );
+
+        let values: Vec<_> = rec.flist.iter().map(|f| f.value().clone()).collect();
+        let params: Vec<&dyn rusqlite::ToSql> =
+            values.iter().map(|v| v as &dyn rusqlite::ToSql).collect();
+
+        self.conn
+            .execute(&sql, &params[..])
+            .unwrap_or_else(|e| panic!("unable to insert into {}: {}", rec.name, e));
+    }
+}
@@ -1,27 +1,27 @@
-use std::error::Error;
 use std::fs::File;
 use std::io::{BufWriter, Write};
+use std::path::Path;
 
-use crate::writer::writer::Writer;
-use rbf::record::{AsciiMode, Record, UTF8Mode};
+use crate::writer::writer::{append_extension, Writer};
+use rbf::record::Record;
 
 pub struct TextWriter {
     last_recname: String,
     buffer: BufWriter<File>,
 }
 
-impl Writer for TextWriter {
-    fn new(input_file: &str) -> TextWriter {
+impl<M> Writer<M> for TextWriter {
+    fn new(input_file: &Path) -> TextWriter {
         // build output file name
-        let output_file = input_file.to_owned() + ".txt";
+        let output_file = append_extension(input_file, ".txt");
 
         // open file for reading
         let file = match File::create(&output_file) {
             // if ok, create a new BufReader to read the file line by line
             Ok(f) => f,
-            // The `description` method of `io::Error` returns a string that
-            // describes the error
-            Err(why) => panic!("couldn't open {}: {}", output_file, why.description()),
+            // `Display` now carries the full error text, so there's no need for the
+            // long-deprecated `Error::description()`
+            Err(why) => panic!("couldn't open {}: {}", output_file.display(), why),
         };
 
         let buffer = BufWriter::new(file);
@@ -34,7 +34,7 @@ impl Writer for TextWriter {
     fn close(&self) {}
 
     #[allow(unused_must_use)]
-    fn write(&mut self, rec: &Record<AsciiMode>) {
+    fn write(&mut self, rec: &Record<M>) {
         // build header from field names only if not the same record than before
         if self.last_recname != rec.name {
             self.buffer.write(b"\n");
@@ -1,20 +1,41 @@
+use std::path::{Path, PathBuf};
+
 use crate::writer::csv::CsvWriter;
+use crate::writer::fixedwidth::FixedWidthWriter;
+use crate::writer::json::JsonWriter;
+use crate::writer::markdown::MarkdownWriter;
+use crate::writer::ndjson::NdjsonWriter;
+use crate::writer::sqlite::SqliteWriter;
 use crate::writer::text::TextWriter;
-use rbf::record::{AsciiMode, Record, UTF8Mode};
+use rbf::record::Record;
+
+/// Builds the output file path by replacing `input_file`'s extension with `ext`
+/// (leading dot optional), e.g. `append_extension("data.dat", ".csv")` -> `data.csv`.
+pub fn append_extension<P: AsRef<Path>>(input_file: P, ext: &str) -> PathBuf {
+    input_file.as_ref().with_extension(ext.trim_start_matches('.'))
+}
 
-pub trait Writer {
-    fn new(metadata: &str) -> Self
+/// A sink for parsed records. Generic over the record mode `M` (`AsciiMode`,
+/// `UTF8Mode`, ...) so the same writer can stream either kind of feed.
+pub trait Writer<M> {
+    fn new(metadata: &Path) -> Self
     where
         Self: Sized;
-    fn write(&mut self, rec: &Record<AsciiMode>);
+    fn write(&mut self, rec: &Record<M>);
     fn close(&self);
 }
 
-/// Convenient creation of a Writer
-pub fn create_writer(from: &str, input_file: &str) -> Box<Writer> {
+/// Convenient creation of a Writer. `csv_delimiter` only applies to the "csv" format;
+/// every other format ignores it.
+pub fn create_writer<M>(from: &str, input_file: &Path, csv_delimiter: char) -> Box<Writer<M>> {
     match from {
         "text" => Box::new(TextWriter::new(input_file)),
-        "csv" => Box::new(CsvWriter::new(input_file)),
+        "csv" => Box::new(CsvWriter::with_delimiter(input_file, csv_delimiter)),
+        "markdown" => Box::new(MarkdownWriter::new(input_file)),
+        "ndjson" => Box::new(NdjsonWriter::new(input_file)),
+        "json" => Box::new(JsonWriter::new(input_file)),
+        "fixed" | "ident" => Box::new(FixedWidthWriter::new(input_file)),
+        "sqlite3" => Box::new(SqliteWriter::new(input_file)),
         unknown_type @ _ => panic!("<{}> is not a valid output format", unknown_type),
     }
 }
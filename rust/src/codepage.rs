@@ -0,0 +1,66 @@
+//! Single-byte EBCDIC code pages used by [`crate::record::EbcdicMode`] to decode mainframe
+//! fixed-width files: each table maps a raw byte to the Unicode char it represents.
+
+/// IBM code page 037 (the most common EBCDIC variant, US/Canada). Bytes 0x00-0x3F decode
+/// to the char of the same value (they are not translated as EBCDIC control characters);
+/// any other byte with no assigned printable character decodes to `'\u{FFFD}'` (the
+/// Unicode replacement character).
+pub fn cp037() -> [char; 256] {
+    let mut table = ['\u{FFFD}'; 256];
+
+    // bytes up to 0x3F pass through as the char of the same value
+    for b in 0x00..=0x3F {
+        table[b] = b as u8 as char;
+    }
+
+    table[0x40] = ' ';
+    table[0x4B] = '.';
+    table[0x4C] = '<';
+    table[0x4D] = '(';
+    table[0x4E] = '+';
+    table[0x4F] = '|';
+    table[0x50] = '&';
+    table[0x5A] = '!';
+    table[0x5B] = '$';
+    table[0x5C] = '*';
+    table[0x5D] = ')';
+    table[0x5E] = ';';
+    table[0x5F] = '¬';
+    table[0x60] = '-';
+    table[0x61] = '/';
+    table[0x6B] = ',';
+    table[0x6C] = '%';
+    table[0x6D] = '_';
+    table[0x6E] = '>';
+    table[0x6F] = '?';
+    table[0x7A] = ':';
+    table[0x7B] = '#';
+    table[0x7C] = '@';
+    table[0x7D] = '\'';
+    table[0x7E] = '=';
+    table[0x7F] = '"';
+
+    for (i, c) in ('a'..='i').enumerate() {
+        table[0x81 + i] = c;
+    }
+    for (i, c) in ('j'..='r').enumerate() {
+        table[0x91 + i] = c;
+    }
+    for (i, c) in ('s'..='z').enumerate() {
+        table[0xA2 + i] = c;
+    }
+    for (i, c) in ('A'..='I').enumerate() {
+        table[0xC1 + i] = c;
+    }
+    for (i, c) in ('J'..='R').enumerate() {
+        table[0xD1 + i] = c;
+    }
+    for (i, c) in ('S'..='Z').enumerate() {
+        table[0xE2 + i] = c;
+    }
+    for (i, c) in ('0'..='9').enumerate() {
+        table[0xF0 + i] = c;
+    }
+
+    table
+}
@@ -21,6 +21,7 @@ pub const MSG0101: &'static str = "cannot create FieldDataType with an empty str
 pub const MSG0110: &'static str = "cannot create a field with an empty name!";
 pub const MSG0111: &'static str = "cannot create Field with a null length!";
 pub const MSG0112: &'static str = "error creating field {}: lower offset {} > upper offset {}!";
+pub const MSG0113: &'static str = "cannot create a field with an empty directory tag!";
 
 //
 //pub const MSG0111: &'static str = "error creating field {}: lower offset {} > upper offset {}!";
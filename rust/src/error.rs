@@ -33,27 +33,58 @@ pub enum RbfError {
 
     /// no field type defined in the layout file
     ErrorLayoutNoFieldType(String, String, String),
-}
 
-impl Error for RbfError {
-    fn description(&self) -> &str {
-        match *self {
-            RbfError::NoFieldTypeFound => "The field type specified in layout is not existing",
-            RbfError::ErrorOpeningLayoutFile(_, _) => "the layout file could not be opened",
-            RbfError::ErrorReadingLayoutFile(_, _) => {
-                "an error occured when reading the layout file"
-            }
-            RbfError::ErrorLayoutNoFieldType(_, _, _) => {
-                "an error occured when reading the layout file"
-            }
-        }
-    }
+    /// a plain io error, not tied to a specific layout or data file
+    Io(::std::io::Error),
+
+    /// a plain XML parsing error, not tied to a specific layout file
+    Xml(::xml::reader::Error),
+
+    /// a field value could not be converted to its declared base type
+    InvalidFieldValue(String),
+
+    /// a `FieldType` was created with an empty id
+    EmptyId,
+
+    /// the type name given to a `BaseType` factory is not one of the known types
+    UnknownType(String),
+
+    /// a regex pattern failed to compile
+    BadRegex(::regex::Error),
+
+    /// a field name referenced by a filter is not found in the layout
+    UnknownField(String),
+
+    /// an expression string (filter, range, ...) did not match the expected grammar
+    Malformed { context: String, reason: String },
+
+    /// a record name passed to a `Layout` write operation is not found in the layout
+    UnknownRecord(String),
+
+    /// a value given to `Layout::write_record` violates its field's declared pattern
+    PatternMismatch { field: String, value: String },
+
+    /// the record assembled by `Layout::write_record` doesn't match its declared length
+    LengthMismatch {
+        record: String,
+        expected: usize,
+        actual: usize,
+    },
+
+    /// `Record::deserialize` failed: a field was missing, declared more than once
+    /// where a single value was expected, or its value didn't parse into the
+    /// destination type
+    Deserialization(String),
+
+    /// a Lua mapper/hook script failed to compile, or raised an error at runtime
+    #[cfg(feature = "lua")]
+    Lua(::mlua::Error),
 }
 
 impl fmt::Display for RbfError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
-            RbfError::NoFieldTypeFound => write!(f, ""),
+            RbfError::NoFieldTypeFound => write!(f, "the field type specified in layout is not existing"),
             RbfError::ErrorOpeningLayoutFile(ref file, ref e) => write!(
                 f,
                 "the layout file <{}> could not be opened, io error={}",
@@ -69,9 +100,104 @@ impl fmt::Display for RbfError {
                 "no field type <{}> for field name <{}> found in layout file <{}>",
                 f_type, f_name, file
             ),
+            RbfError::Io(ref e) => write!(f, "io error: {}", e),
+            RbfError::Xml(ref e) => write!(f, "xml error: {}", e),
+            RbfError::InvalidFieldValue(ref value) => {
+                write!(f, "unable to convert string value {}", value)
+            }
+            RbfError::EmptyId => write!(f, "cannot create a FieldType with an empty id"),
+            RbfError::UnknownType(ref t) => write!(f, "<{}> is not allowed as a field type", t),
+            RbfError::BadRegex(ref e) => write!(f, "invalid regex pattern: {}", e),
+            RbfError::UnknownField(ref field) => {
+                write!(f, "field name {} is not found in the layout", field)
+            }
+            RbfError::Malformed {
+                ref context,
+                ref reason,
+            } => write!(f, "malformed {}: {}", context, reason),
+            RbfError::UnknownRecord(ref rec) => {
+                write!(f, "record name {} is not found in the layout", rec)
+            }
+            RbfError::PatternMismatch {
+                ref field,
+                ref value,
+            } => write!(
+                f,
+                "value \"{}\" does not match the pattern declared for field {}",
+                value, field
+            ),
+            RbfError::LengthMismatch {
+                ref record,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "record {} has length {}, expected {}",
+                record, actual, expected
+            ),
+            RbfError::Deserialization(ref reason) => {
+                write!(f, "error deserializing record: {}", reason)
+            }
+            #[cfg(feature = "lua")]
+            RbfError::Lua(ref e) => write!(f, "Lua error: {}", e),
+        }
+    }
+}
+
+impl Error for RbfError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            RbfError::NoFieldTypeFound => None,
+            RbfError::ErrorOpeningLayoutFile(_, ref e) => Some(e),
+            RbfError::ErrorReadingLayoutFile(_, ref e) => Some(e),
+            RbfError::ErrorLayoutNoFieldType(_, _, _) => None,
+            RbfError::Io(ref e) => Some(e),
+            RbfError::Xml(ref e) => Some(e),
+            RbfError::InvalidFieldValue(_) => None,
+            RbfError::EmptyId => None,
+            RbfError::UnknownType(_) => None,
+            RbfError::BadRegex(ref e) => Some(e),
+            RbfError::UnknownField(_) => None,
+            RbfError::Malformed { .. } => None,
+            RbfError::UnknownRecord(_) => None,
+            RbfError::PatternMismatch { .. } => None,
+            RbfError::LengthMismatch { .. } => None,
+            RbfError::Deserialization(_) => None,
+            #[cfg(feature = "lua")]
+            RbfError::Lua(ref e) => Some(e),
         }
     }
 }
 
-/*panic!("couldn't open {}: {}, current directory is: {}",
-xml_file, why.description(), env::current_dir().unwrap().display()),*/
+impl From<::std::io::Error> for RbfError {
+    fn from(e: ::std::io::Error) -> RbfError {
+        RbfError::Io(e)
+    }
+}
+
+impl From<::xml::reader::Error> for RbfError {
+    fn from(e: ::xml::reader::Error) -> RbfError {
+        RbfError::Xml(e)
+    }
+}
+
+impl From<::regex::Error> for RbfError {
+    fn from(e: ::regex::Error) -> RbfError {
+        RbfError::BadRegex(e)
+    }
+}
+
+#[cfg(feature = "lua")]
+impl From<::mlua::Error> for RbfError {
+    fn from(e: ::mlua::Error) -> RbfError {
+        RbfError::Lua(e)
+    }
+}
+
+/// Lets `RbfError` act as the error type of a custom `serde::de::Deserializer`
+/// (see `Record::deserialize`).
+impl ::serde::de::Error for RbfError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        RbfError::Deserialization(msg.to_string())
+    }
+}
@@ -3,6 +3,87 @@ use crate::layout::Layout;
 
 pub trait Exportable {
     fn to_html(&self) -> String;
+
+    /// Renders as a GitHub-flavored markdown table, with no column wrapping.
+    fn to_markdown(&self) -> String {
+        self.to_markdown_wrapped(0)
+    }
+
+    /// Renders as a GitHub-flavored markdown table, wrapping any column wider than
+    /// `max_width` chars onto continuation rows (0 means unbounded).
+    fn to_markdown_wrapped(&self, max_width: usize) -> String;
+}
+
+/// Slices `text` into chunks of at most `width` chars, on char boundaries. `width ==
+/// 0` disables wrapping (the whole text is kept as a single chunk).
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    if width == 0 || text.chars().count() <= width {
+        return vec![text.to_string()];
+    }
+
+    text.chars()
+        .collect::<Vec<_>>()
+        .chunks(width)
+        .map(|c| c.iter().collect())
+        .collect()
+}
+
+/// Builds a GitHub-flavored markdown table from a header row and data rows. Each
+/// column is first sized to the max of the header and all cell lengths, then capped
+/// at `max_width` chars (0 means unbounded). Cells longer than the cap are wrapped
+/// onto continuation rows, with the other columns left blank on those rows, and
+/// every cell is padded out to its column's width.
+fn markdown_table(headers: &[&str], rows: &[Vec<String>], max_width: usize) -> String {
+    let mut widths: Vec<usize> = headers.iter().map(|h| h.chars().count()).collect();
+    for row in rows {
+        for (i, cell) in row.iter().enumerate() {
+            widths[i] = widths[i].max(cell.chars().count());
+        }
+    }
+
+    if max_width > 0 {
+        for w in widths.iter_mut() {
+            *w = (*w).min(max_width);
+        }
+    }
+
+    let mut s = String::new();
+
+    let header_cells: Vec<_> = headers
+        .iter()
+        .enumerate()
+        .map(|(i, h)| format!("{:width$}", h, width = widths[i]))
+        .collect();
+    s += &format!("| {} |\n", header_cells.join(" | "));
+
+    let sep_cells: Vec<_> = headers.iter().map(|_| "---").collect();
+    s += &format!("| {} |\n", sep_cells.join(" | "));
+
+    for row in rows {
+        let chunks: Vec<Vec<String>> = row
+            .iter()
+            .enumerate()
+            .map(|(i, cell)| wrap(cell, widths[i]))
+            .collect();
+        let nlines = chunks.iter().map(|c| c.len()).max().unwrap_or(1);
+
+        for line in 0..nlines {
+            let cells: Vec<_> = chunks
+                .iter()
+                .enumerate()
+                .map(|(i, c)| {
+                    format!(
+                        "{:width$}",
+                        c.get(line).map(String::as_str).unwrap_or(""),
+                        width = widths[i]
+                    )
+                })
+                .collect();
+            s += &format!("| {} |\n", cells.join(" | "));
+        }
+    }
+
+    s
 }
 
 impl<T> Exportable for Record<T> {
@@ -20,8 +101,10 @@ impl<T> Exportable for Record<T> {
         // fields description
         s += format!("<table class=\"table table-striped\">").as_str();
         s += format!("<thead><tr><th>#</th><th>Field name</th><th>Description</th>").as_str();
-        s +=
-            format!("<th>Type</th><th>Length</th><th>Start</th><th>End</th></tr></thead>").as_str();
+        s += format!(
+            "<th>Type</th><th>Length</th><th>Start</th><th>End</th><th>Value</th></tr></thead>"
+        )
+        .as_str();
 
         for f in self {
             s += format!(
@@ -36,11 +119,21 @@ impl<T> Exportable for Record<T> {
             )
             .as_str();
             s += format!(
-                "<td>{}</td><td>{}</td></tr>",
+                "<td>{}</td><td>{}</td><td>{}</td></tr>",
                 f.lower_offset + 1,
-                f.upper_offset + 1
+                f.upper_offset + 1,
+                if f.is_present() { f.value().as_str() } else { "—" }
             )
             .as_str();
+
+            // a composite field expands into one nested row per subfield
+            for (id, value) in f.subfields() {
+                s += format!(
+                    "<tr><td></td><td colspan=\"6\">↳ <em>{}</em></td><td>{}</td></tr>",
+                    id, value
+                )
+                .as_str();
+            }
         }
 
         // close HTML table
@@ -48,6 +141,66 @@ impl<T> Exportable for Record<T> {
 
         s
     }
+
+    /// Converts a record's field layout to a markdown table, wrapping any column
+    /// wider than `max_width` chars.
+    fn to_markdown_wrapped(&self, max_width: usize) -> String {
+        let mut s = format!(
+            "## {}-{}-{}\n\n",
+            self.name, self.description, self.calculated_length
+        );
+
+        let headers = [
+            "#",
+            "Field name",
+            "Description",
+            "Type",
+            "Length",
+            "Start",
+            "End",
+            "Value",
+        ];
+
+        let rows: Vec<Vec<String>> = self
+            .into_iter()
+            .flat_map(|f| {
+                let mut field_rows = vec![vec![
+                    (f.index + 1).to_string(),
+                    f.name.clone(),
+                    f.description.clone(),
+                    f.ftype.id.clone(),
+                    f.length.to_string(),
+                    (f.lower_offset + 1).to_string(),
+                    (f.upper_offset + 1).to_string(),
+                    if f.is_present() {
+                        f.value().clone()
+                    } else {
+                        "—".to_string()
+                    },
+                ]];
+
+                // a composite field expands into one nested row per subfield
+                for (id, value) in f.subfields() {
+                    field_rows.push(vec![
+                        String::new(),
+                        format!("↳ {}", id),
+                        String::new(),
+                        String::new(),
+                        String::new(),
+                        String::new(),
+                        String::new(),
+                        value.to_string(),
+                    ]);
+                }
+
+                field_rows
+            })
+            .collect();
+
+        s += &markdown_table(&headers, &rows, max_width);
+
+        s
+    }
 }
 
 impl<T> Exportable for Layout<T> {
@@ -131,4 +284,51 @@ impl<T> Exportable for Layout<T> {
 
         s
     }
+
+    /// Converts the whole layout to a markdown document: metadata, field types,
+    /// then each record's field layout, sorted alphanumerically. Wraps any column
+    /// wider than `max_width` chars.
+    fn to_markdown_wrapped(&self, max_width: usize) -> String {
+        let mut s = format!("# {} ({})\n\n", self.description, self.version);
+
+        s += &markdown_table(
+            &["Metadata", "Value"],
+            &[
+                vec!["Record length".to_string(), self.rec_length.to_string()],
+                vec!["Version".to_string(), self.version.clone()],
+                vec!["Description".to_string(), self.description.clone()],
+                vec!["Schema".to_string(), self.schema.clone()],
+            ],
+            max_width,
+        );
+        s += "\n";
+
+        let mut ftypes: Vec<_> = self.ftypes.keys().collect();
+        ftypes.sort();
+
+        let ftype_rows: Vec<Vec<String>> = ftypes
+            .iter()
+            .map(|ftype| {
+                let ft = self.ftypes.get(*ftype).unwrap();
+                vec![
+                    ft.id.clone(),
+                    ft.type_as_string.clone(),
+                    ft.pattern.as_str().to_string(),
+                ]
+            })
+            .collect();
+
+        s += &markdown_table(&["Field type", "Type of data", "Pattern"], &ftype_rows, max_width);
+        s += "\n";
+
+        let mut rec_names: Vec<_> = self.rec_map.keys().collect();
+        rec_names.sort();
+
+        for recname in rec_names {
+            s += &self.get(&recname).unwrap().to_markdown_wrapped(max_width);
+            s += "\n";
+        }
+
+        s
+    }
 }
@@ -35,11 +35,23 @@ use crate::error_msg;
 use crate::filter::fieldfilter::{FieldFilter, FieldFilterOp};
 use crate::types::fieldtype::FieldType;
 
-/// Holds the way a **Field** is defined: by giving its length or its offsets
+/// Holds the way a **Field** is defined: by giving its length, its offsets, or (for
+/// directory-based records, see [`DirectoryMode`](../record/struct.DirectoryMode.html))
+/// a tag resolved against the record's in-band directory at parse time.
 #[derive(Debug, Clone)]
 pub enum FieldCreationType {
     ByLength,
     ByOffset,
+    ByTag,
+}
+
+/// One subfield declared within a composite field (see
+/// [`Field::subfield_delimiter`]): a name and the single-character identifier that
+/// introduces it in the raw value, e.g. MARC subfield `$a`.
+#[derive(Debug, Clone)]
+pub struct Subfield {
+    pub name: String,
+    pub identifier: char,
 }
 
 #[derive(Debug, Clone)]
@@ -72,6 +84,21 @@ pub struct Field {
     pub creation_type: FieldCreationType,
     /// unique name associated to the field: in case of field duplication, this name is unique
     pub id: String,
+    /// for `ByTag` fields: the directory tag this field is resolved from, e.g. `"245"`
+    /// in a MARC-style record. Empty for `ByLength`/`ByOffset` fields.
+    pub tag: String,
+    /// byte separating subfields packed into this field's raw value (e.g. MARC's
+    /// `0x1F`). `None` if the field isn't composite.
+    pub subfield_delimiter: Option<char>,
+    /// subfields declared for this field, in the order declared in the layout.
+    /// Empty if the field isn't composite.
+    pub subfields: Vec<Subfield>,
+    /// when `true`, an all-blank value means this field is absent rather than a
+    /// value failing its type's pattern: see [`Field::is_present`].
+    pub optional: bool,
+    /// untouched bytes set through [`Field::set_value_bytes`], for fields read from
+    /// input that isn't assumed to be valid UTF-8. Empty unless that method was used.
+    pub raw_bytes: Vec<u8>,
 }
 
 impl Field {
@@ -142,9 +169,54 @@ impl Field {
             cell_size: max(length, name.len()),
             creation_type: FieldCreationType::ByLength,
             id: String::new(),
+            tag: String::new(),
+            subfield_delimiter: None,
+            subfields: Vec::new(),
+            optional: false,
+            raw_bytes: Vec::new(),
         }
     }
 
+    /// Creates a new optional field with length: just like [`Field::from_length`],
+    /// but an all-blank value means the field is absent (see [`Field::is_present`])
+    /// rather than a value failing its type's pattern. Useful for fixed-width feeds
+    /// where short records omit trailing optional columns.
+    ///
+    /// # Panics
+    /// If `name` is empty or `length` is 0
+    ///
+    /// # Examples
+    /// ```
+    /// use std::rc::Rc;
+    ///
+    /// use rbf::types::fieldtype::FieldType;
+    /// use rbf::field::Field;
+    ///
+    /// let mut ft = FieldType::new("I", "int");
+    /// ft.set_pattern("\\d+");
+    /// let ft = Rc::new(ft);
+    ///
+    /// let mut f = Field::optional_from_length("F1", "Description for field 1", &ft, 10);
+    /// f.set_value("          ");
+    /// assert!(!f.is_present());
+    /// assert_eq!(f.value(), "");
+    /// assert!(f.is_pattern_matched());
+    ///
+    /// f.set_value("       123");
+    /// assert!(f.is_present());
+    /// assert!(f.is_pattern_matched());
+    /// ```
+    pub fn optional_from_length(
+        name: &str,
+        description: &str,
+        ftype: &Rc<FieldType>,
+        length: usize,
+    ) -> Field {
+        let mut field = Field::from_length(name, description, ftype, length);
+        field.optional = true;
+        field
+    }
+
     /// Creates a new field with lower & upper bounds.
     ///
     /// # Arguments
@@ -221,6 +293,80 @@ impl Field {
             cell_size: max(length, name.len()),
             creation_type: FieldCreationType::ByOffset,
             id: String::new(),
+            tag: String::new(),
+            subfield_delimiter: None,
+            subfields: Vec::new(),
+            optional: false,
+            raw_bytes: Vec::new(),
+        }
+    }
+
+    /// Creates a new field resolved by directory tag rather than a static length or
+    /// offset: its length and position within a record are only known once the
+    /// record's in-band directory (see [`DirectoryMode`](../record/struct.DirectoryMode.html))
+    /// has been parsed.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - name of the field
+    /// * `description`: description of the field
+    /// * `FieldType` fieldtype: format of the field (type of data found in the field)
+    /// * `tag`: the directory tag this field is resolved from, e.g. `"245"`
+    ///
+    /// # Panics
+    /// If `name` or `tag` is empty
+    ///
+    /// # Examples
+    /// ```
+    /// use std::rc::Rc;
+    ///
+    /// use rbf::types::fieldtype::FieldType;
+    /// use rbf::field::Field;
+    ///
+    /// let ft = Rc::new(FieldType::new("S", "string"));
+    /// let f1 = Field::from_tag("TITLE", "Title field", &ft, "245");
+    ///
+    /// assert_eq!(&f1.name, "TITLE");
+    /// assert_eq!(&f1.tag, "245");
+    /// ```
+    ///
+    /// ```should_panic
+    /// use std::rc::Rc;
+    ///
+    /// use rbf::types::fieldtype::FieldType;
+    /// use rbf::field::Field;
+    ///
+    /// let ft = Rc::new(FieldType::new("S", "string"));
+    /// let f1 = Field::from_tag("TITLE", "Title field", &ft, "");
+    /// ```
+    pub fn from_tag(name: &str, description: &str, ftype: &Rc<FieldType>, tag: &str) -> Field {
+        if name.is_empty() {
+            panic!(MSG0110);
+        }
+        if tag.is_empty() {
+            panic!(MSG0113);
+        }
+
+        Field {
+            name: name.to_string(),
+            description: description.to_string(),
+            length: 0,
+            ftype: ftype.clone(),
+            raw_value: String::new(),
+            str_value: String::new(),
+            offset_from_origin: 0,
+            index: 0,
+            lower_offset: 0,
+            upper_offset: 0,
+            multiplicity: 0,
+            cell_size: max(tag.len(), name.len()),
+            creation_type: FieldCreationType::ByTag,
+            id: String::new(),
+            tag: tag.to_string(),
+            subfield_delimiter: None,
+            subfields: Vec::new(),
+            optional: false,
+            raw_bytes: Vec::new(),
         }
     }
 
@@ -275,6 +421,42 @@ impl Field {
         &self.str_value
     }
 
+    /// Sets the value from a raw byte slice, kept as-is in [`Field::raw_bytes`]: unlike
+    /// [`Field::set_value`], this never assumes the slice is valid UTF-8, so it never
+    /// panics or mangles binary-tainted input. `raw_value`/`str_value` are left untouched;
+    /// use [`Field::value_lossy`] to get a string out of it.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::rc::Rc;
+    ///
+    /// use rbf::types::fieldtype::FieldType;
+    /// use rbf::field::Field;
+    ///
+    /// let ft = Rc::new(FieldType::new("S", "string"));
+    /// let mut f = Field::from_length("F1", "Description for field 1", &ft, 3);
+    ///
+    /// // 0xFF is not valid UTF-8 on its own
+    /// f.set_value_bytes(&[b'A', 0xFF, b'B']);
+    /// assert_eq!(f.as_bytes(), &[b'A', 0xFF, b'B']);
+    /// assert_eq!(f.value_lossy(), "A\u{FFFD}B");
+    /// ```
+    pub fn set_value_bytes(&mut self, bytes: &[u8]) {
+        self.raw_bytes = bytes.to_vec();
+    }
+
+    /// Returns the untouched bytes set through [`Field::set_value_bytes`]. Empty if
+    /// that method was never called on this field.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.raw_bytes
+    }
+
+    /// Lossily converts [`Field::raw_bytes`] to a `String`, replacing any invalid
+    /// UTF-8 with `U+FFFD`. Computed on demand: nothing is cached.
+    pub fn value_lossy(&self) -> String {
+        String::from_utf8_lossy(&self.raw_bytes).into_owned()
+    }
+
     /// Returns the total number of chars in the fields.
     /// # Examples
     /// ```
@@ -292,30 +474,126 @@ impl Field {
         self.length
     }
 
-    /// Verifies if the field value is matching the field type pattern.
+    /// Declares this field composite: `delimiter` separates subfields packed into
+    /// the raw value, each introduced by its [`Subfield::identifier`] (e.g. MARC's
+    /// `0x1F`/`$a`).
     ///
     /// # Examples
     /// ```
     /// use std::rc::Rc;
     ///
     /// use rbf::types::fieldtype::FieldType;
-    /// use rbf::field::Field;  
-    ///  
+    /// use rbf::field::{Field, Subfield};
+    ///
+    /// let ft = Rc::new(FieldType::new("S", "string"));
+    /// let mut f = Field::from_length("F1", "Description for field 1", &ft, 20);
+    /// f.set_subfields('\u{1F}', vec![
+    ///     Subfield { name: "title".to_string(), identifier: 'a' },
+    ///     Subfield { name: "subtitle".to_string(), identifier: 'b' },
+    /// ]);
+    ///
+    /// f.set_value("\u{1F}aDune\u{1F}bA novel");
+    /// assert_eq!(f.subfield("title"), Some("Dune"));
+    /// assert_eq!(f.subfield("subtitle"), Some("A novel"));
+    /// assert_eq!(f.subfield("unknown"), None);
+    /// ```
+    pub fn set_subfields(&mut self, delimiter: char, subfields: Vec<Subfield>) {
+        self.subfield_delimiter = Some(delimiter);
+        self.subfields = subfields;
+    }
+
+    /// Returns the value of the subfield named `name`, if this field is composite
+    /// and declares it. The field's value is split on `subfield_delimiter`, and the
+    /// chunk whose leading character is the subfield's identifier is returned, with
+    /// that identifier stripped off.
+    pub fn subfield(&self, name: &str) -> Option<&str> {
+        let delimiter = self.subfield_delimiter?;
+        let identifier = self.subfields.iter().find(|s| s.name == name)?.identifier;
+
+        self.value()
+            .split(delimiter)
+            .find_map(|chunk| chunk.strip_prefix(identifier))
+    }
+
+    /// Returns every subfield packed into this field's raw value as `(identifier,
+    /// value)` pairs, in the order they appear, regardless of whether a matching
+    /// [`Subfield`] was declared. Empty if the field isn't composite. The segment
+    /// before the first delimiter, if any (an unkeyed prefix), is not included.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::rc::Rc;
+    ///
+    /// use rbf::types::fieldtype::FieldType;
+    /// use rbf::field::Field;
+    ///
+    /// let ft = Rc::new(FieldType::new("S", "string"));
+    /// let mut f = Field::from_length("F1", "Description for field 1", &ft, 20);
+    /// f.set_subfields('\u{1F}', Vec::new());
+    /// f.set_value("\u{1F}aDune\u{1F}bA novel");
+    ///
+    /// assert_eq!(f.subfields(), vec![('a', "Dune"), ('b', "A novel")]);
+    /// ```
+    pub fn subfields(&self) -> Vec<(char, &str)> {
+        let delimiter = match self.subfield_delimiter {
+            Some(d) => d,
+            None => return Vec::new(),
+        };
+
+        self.value()
+            .split(delimiter)
+            .skip(1)
+            .filter_map(|chunk| {
+                let id = chunk.chars().next()?;
+                Some((id, &chunk[id.len_utf8()..]))
+            })
+            .collect()
+    }
+
+    /// Returns the value of the subfield identified by `id`, if this field is
+    /// composite. Unlike [`Field::subfield`], this doesn't require a matching
+    /// [`Subfield`] to have been declared in the layout.
+    pub fn subfield_by_id(&self, id: char) -> Option<&str> {
+        self.subfields().into_iter().find(|(i, _)| *i == id).map(|(_, v)| v)
+    }
+
+    /// Reports whether this field actually holds a value. Always `true` for
+    /// non-optional fields; for an [`Field::optional_from_length`] field, `false`
+    /// once [`Field::set_value`] has been given an all-blank slice.
+    pub fn is_present(&self) -> bool {
+        !self.optional || !self.str_value.is_empty()
+    }
+
+    /// Verifies if the field value is matching the field type pattern. An absent
+    /// optional field (see [`Field::is_present`]) always matches: blanks don't need
+    /// to satisfy the type's pattern when there's no value to validate.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::rc::Rc;
+    ///
+    /// use rbf::types::fieldtype::FieldType;
+    /// use rbf::field::Field;
+    ///
     /// let mut ft = FieldType::new("I", "int");
     /// ft.set_pattern("\\d+");
     ///
     /// let mut f = Field::from_offset("F1", "Description for field 1", &Rc::new(ft), 5, 10);
-    /// f.set_value("123");  
+    /// f.set_value("123");
     /// assert!(f.is_pattern_matched());
     ///
-    /// f.set_value("ABC");  
-    /// assert!(!f.is_pattern_matched());  
-    /// ```         
+    /// f.set_value("ABC");
+    /// assert!(!f.is_pattern_matched());
+    /// ```
     pub fn is_pattern_matched(&self) -> bool {
+        if !self.is_present() {
+            return true;
+        }
         self.ftype.pattern.is_match(&self.raw_value)
     }
 
-    /// Checks if the field value matches the field filter
+    /// Checks if the field value matches the field filter. An absent optional field
+    /// (see [`Field::is_present`]) always matches.
     ///
     /// # Examples
     /// ```
@@ -336,6 +614,10 @@ impl Field {
     /// assert!(!f.is_filter_matched(&expr));     
     /// ```       
     pub fn is_filter_matched(&self, filter: &FieldFilter) -> bool {
+        if !self.is_present() {
+            return true;
+        }
+
         let result = match filter.op {
             FieldFilterOp::OpEqual => self
                 .ftype
@@ -355,6 +637,31 @@ impl Field {
                 .ftype
                 .base_type
                 .gt(self.value(), filter.freg_or_value.as_str()),
+            FieldFilterOp::OpLessOrEqual => self
+                .ftype
+                .base_type
+                .le(self.value(), filter.freg_or_value.as_str()),
+            FieldFilterOp::OpGreaterOrEqual => self
+                .ftype
+                .base_type
+                .ge(self.value(), filter.freg_or_value.as_str()),
+            FieldFilterOp::OpInRange => {
+                let (start, end) = filter
+                    .range
+                    .as_ref()
+                    .expect("range bounds missing for an OpInRange filter");
+
+                let above_start = match start {
+                    Some(s) => self.ftype.base_type.ge(self.value(), s),
+                    None => true,
+                };
+                let below_end = match end {
+                    Some(e) => self.ftype.base_type.le(self.value(), e),
+                    None => true,
+                };
+
+                above_start && below_end
+            }
         };
         result
     }
@@ -1,6 +1,9 @@
 use regex::Regex;
+use std::convert::TryFrom;
 use std::fmt;
 
+use crate::error::RbfError;
+
 #[derive(Debug, PartialEq)]
 pub enum FieldFilterOp {
     OpEqual,
@@ -9,37 +12,58 @@ pub enum FieldFilterOp {
     OpNotSimilar,
     OpLessThan,
     OpGreaterThan,
+    OpLessOrEqual,
+    OpGreaterOrEqual,
+    /// inclusive range membership, e.g. `EVENT_TS <> 2020-01-01..2020-12-31`
+    OpInRange,
+}
+
+/// Fallible conversion from a string ref: the non-panicking counterpart of
+/// `From<&str> for FieldFilterOp`.
+impl<'a> TryFrom<&'a str> for FieldFilterOp {
+    type Error = RbfError;
+
+    fn try_from(op: &'a str) -> Result<FieldFilterOp, RbfError> {
+        match op {
+            "=" => Ok(FieldFilterOp::OpEqual),
+            "!=" => Ok(FieldFilterOp::OpNotEqual),
+            "~" => Ok(FieldFilterOp::OpSimilar),
+            "!~" => Ok(FieldFilterOp::OpNotSimilar),
+            "<" => Ok(FieldFilterOp::OpLessThan),
+            ">" => Ok(FieldFilterOp::OpGreaterThan),
+            "<=" => Ok(FieldFilterOp::OpLessOrEqual),
+            ">=" => Ok(FieldFilterOp::OpGreaterOrEqual),
+            "<>" => Ok(FieldFilterOp::OpInRange),
+            unknown_op @ _ => Err(RbfError::Malformed {
+                context: "field filter operator".to_string(),
+                reason: format!("<{}> is not allowed as a field expression operator", unknown_op),
+            }),
+        }
+    }
 }
 
 /// Convenient conversion from a string ref.
 ///
+/// # Panics
+/// If `op` isn't a known operator. Prefer `TryFrom` when a non-panicking
+/// conversion is needed.
+///
 /// # Examples
 ///
 /// ```
 /// use rbf::filter::fieldfilter::FieldFilterOp;
-///    
+///
 /// let ffop = FieldFilterOp::from("<");
 /// ```
 ///
 /// ```should_panic
 /// use rbf::filter::fieldfilter::FieldFilterOp;
-///    
+///
 /// let ffop = FieldFilterOp::from("#");
 /// ```
 impl<'a> From<&'a str> for FieldFilterOp {
     fn from(op: &'a str) -> FieldFilterOp {
-        match op {
-            "=" => FieldFilterOp::OpEqual,
-            "!=" => FieldFilterOp::OpNotEqual,
-            "~" => FieldFilterOp::OpSimilar,
-            "!~" => FieldFilterOp::OpNotSimilar,
-            "<" => FieldFilterOp::OpLessThan,
-            ">" => FieldFilterOp::OpGreaterThan,
-            unknown_op @ _ => panic!(
-                "<{}> is not allowed as a field expression operator",
-                unknown_op
-            ),
-        }
+        FieldFilterOp::try_from(op).expect("invalid field filter operator")
     }
 }
 
@@ -53,15 +77,73 @@ pub struct FieldFilter {
     pub op: FieldFilterOp,
     // regex value to match
     pub freg_or_value: Regex,
+    // populated only when `op` is `OpInRange`: inclusive (start, end) bounds, `None`
+    // on either side meaning unbounded, e.g. `..2020-12-31` or `2020-01-01..`
+    pub range: Option<(Option<String>, Option<String>)>,
+}
+
+/// Splits a `start..end` range expression into inclusive, possibly unbounded, bounds.
+/// Bounds are kept as plain strings and compared through the field's declared
+/// `BaseType` once the filter is evaluated against an actual record. The `start <= end`
+/// check made here, before that type is known, orders two numeric bounds as numbers
+/// and any other bounds as strings.
+///
+/// # Panics
+/// If `value` isn't of the form `start..end`, or `start > end`. Prefer
+/// [`try_parse_range`] when a non-panicking conversion is needed.
+fn parse_range(value: &str) -> (Option<String>, Option<String>) {
+    try_parse_range(value).expect("invalid range filter value")
+}
+
+/// Fallible counterpart of [`parse_range`].
+fn try_parse_range(value: &str) -> Result<(Option<String>, Option<String>), RbfError> {
+    let parts: Vec<&str> = value.splitn(2, "..").collect();
+    if parts.len() != 2 {
+        return Err(RbfError::Malformed {
+            context: "range filter value".to_string(),
+            reason: format!("\"{}\" is not of the form start..end", value),
+        });
+    }
+
+    let start = if parts[0].is_empty() {
+        None
+    } else {
+        Some(parts[0].to_string())
+    };
+    let end = if parts[1].is_empty() {
+        None
+    } else {
+        Some(parts[1].to_string())
+    };
+
+    if let (Some(ref s), Some(ref e)) = (&start, &end) {
+        let out_of_order = match (s.parse::<f64>(), e.parse::<f64>()) {
+            (Ok(a), Ok(b)) => a > b,
+            _ => s > e,
+        };
+        if out_of_order {
+            return Err(RbfError::Malformed {
+                context: "range filter value".to_string(),
+                reason: format!("invalid range \"{}\": start must not be after end", value),
+            });
+        }
+    }
+
+    Ok((start, end))
 }
 
 /// Creates a new field filter
 ///
+/// # Panics
+/// If `op_str` isn't a known operator, `fvalue` isn't a valid regex (or, for
+/// `OpInRange`, a valid `start..end` range). Prefer [`FieldFilter::try_new`] when a
+/// non-panicking conversion is needed.
+///
 /// # Examples
 ///
 /// ```
 /// use rbf::filter::fieldfilter::{FieldFilterOp,FieldFilter};
-///    
+///
 /// let expr = FieldFilter::new("  FIELD1  ", " =  ", " FOO  ");
 /// assert_eq!(expr.fname, "FIELD1");
 /// assert_eq!(expr.op, FieldFilterOp::OpEqual);
@@ -69,12 +151,27 @@ pub struct FieldFilter {
 /// ```
 impl FieldFilter {
     pub fn new(fname: &str, op_str: &str, fvalue: &str) -> FieldFilter {
-        FieldFilter {
+        FieldFilter::try_new(fname, op_str, fvalue).expect("unable to create FieldFilter")
+    }
+
+    /// Fallible counterpart of [`FieldFilter::new`].
+    pub fn try_new(fname: &str, op_str: &str, fvalue: &str) -> Result<FieldFilter, RbfError> {
+        let op = FieldFilterOp::try_from(op_str.trim())?;
+        let value = fvalue.trim();
+
+        let range = if op == FieldFilterOp::OpInRange {
+            Some(try_parse_range(value)?)
+        } else {
+            None
+        };
+
+        Ok(FieldFilter {
             fname: fname.trim().to_string(),
             op_string: op_str.trim().to_owned(),
-            op: FieldFilterOp::from(op_str.trim()),
-            freg_or_value: Regex::new(fvalue.trim()).unwrap(),
-        }
+            op,
+            freg_or_value: Regex::new(value)?,
+            range,
+        })
     }
 }
 
@@ -114,19 +211,57 @@ impl FieldFilter {
 /// assert_eq!(expr.fname, "FIELD1");
 /// assert_eq!(expr.op, FieldFilterOp::OpGreaterThan);
 /// assert_eq!(expr.freg_or_value.as_str(), "10");
+///
+/// expr = FieldFilter::from("FIELD1 <= 10");
+/// assert_eq!(expr.fname, "FIELD1");
+/// assert_eq!(expr.op, FieldFilterOp::OpLessOrEqual);
+/// assert_eq!(expr.freg_or_value.as_str(), "10");
+///
+/// expr = FieldFilter::from("FIELD1 >= 10");
+/// assert_eq!(expr.fname, "FIELD1");
+/// assert_eq!(expr.op, FieldFilterOp::OpGreaterOrEqual);
+/// assert_eq!(expr.freg_or_value.as_str(), "10");
+///
+/// expr = FieldFilter::from("FIELD1 <> 2020-01-01..2020-12-31");
+/// assert_eq!(expr.fname, "FIELD1");
+/// assert_eq!(expr.op, FieldFilterOp::OpInRange);
+/// assert_eq!(
+///     expr.range,
+///     Some((Some("2020-01-01".to_string()), Some("2020-12-31".to_string())))
+/// );
 /// ```
+///
+/// # Panics
+/// If `op` isn't a known operator, or `expr` cannot be split into a `field op value`
+/// triple. Prefer `TryFrom` when a non-panicking conversion is needed.
 impl<'a> From<&'a str> for FieldFilter {
     fn from(expr: &'a str) -> FieldFilter {
+        FieldFilter::try_from(expr).expect("unable to create FieldFilter")
+    }
+}
+
+/// Fallible conversion from a string ref: the non-panicking counterpart of
+/// `From<&str> for FieldFilter`.
+impl<'a> TryFrom<&'a str> for FieldFilter {
+    type Error = RbfError;
+
+    fn try_from(expr: &'a str) -> Result<FieldFilter, RbfError> {
         // regex used to split expression
-        let expr_reg = Regex::new(r"(?P<field>\w+)\s+(?P<op>=|!=|~|!~|<|>)\s+(?P<re>.+)$").unwrap();
+        let expr_reg =
+            Regex::new(r"(?P<field>\w+)\s+(?P<op>=|!=|~|!~|<>|<=|>=|<|>)\s+(?P<re>.+)$").unwrap();
 
         // split according to delimiter
         let caps = match expr_reg.captures(expr) {
             Some(e) => e,
-            None => panic!("unable to find a suitable operator for filter \"{}\"", expr),
+            None => {
+                return Err(RbfError::Malformed {
+                    context: "field filter expression".to_string(),
+                    reason: format!("unable to find a suitable operator for filter \"{}\"", expr),
+                })
+            }
         };
 
-        FieldFilter::new(&caps["field"], &caps["op"], &caps["re"])
+        FieldFilter::try_new(&caps["field"], &caps["op"], &caps["re"])
     }
 }
 
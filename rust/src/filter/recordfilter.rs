@@ -1,46 +1,289 @@
+use std::convert::TryFrom;
+
+use crate::error::RbfError;
 use crate::filter::fieldfilter::FieldFilter;
 use crate::layout::Layout;
+use crate::record::Record;
+
+/// Boolean combination of field filters: a `Cmp` leaf is a single `field op value`
+/// condition, combined with `AND`/`OR`/`NOT` (or the symbolic `&&`/`||`/`!`), standard
+/// precedence (`NOT` > `AND` > `OR`), left-associative, with parentheses for grouping.
+/// `;` is kept as sugar for top-level `AND` so older filter expressions keep working.
+#[derive(Debug)]
+pub enum FilterExpr {
+    Cmp(FieldFilter),
+    And(Box<FilterExpr>, Box<FilterExpr>),
+    Or(Box<FilterExpr>, Box<FilterExpr>),
+    Not(Box<FilterExpr>),
+    Group(Box<FilterExpr>),
+}
+
+impl FilterExpr {
+    /// Evaluates the expression tree against a record, short-circuiting `AND`/`OR`.
+    /// A field absent from the record's layout doesn't exclude it: the leaf is
+    /// considered matched, so the same filter can be reused across record types.
+    pub fn eval<T>(&self, rec: &Record<T>) -> bool {
+        match self {
+            FilterExpr::Cmp(f) => match rec.get(&f.fname) {
+                Some(fields) => fields.iter().any(|x| x.is_filter_matched(f)),
+                None => true,
+            },
+            FilterExpr::Not(e) => !e.eval(rec),
+            FilterExpr::And(lhs, rhs) => lhs.eval(rec) && rhs.eval(rec),
+            FilterExpr::Or(lhs, rhs) => lhs.eval(rec) || rhs.eval(rec),
+            FilterExpr::Group(e) => e.eval(rec),
+        }
+    }
 
-// Char delimiter between field filters on the same condition
-const FIELD_FILTER_DELIMITER: char = ';';
+    /// Collects every field name referenced anywhere in the expression tree.
+    fn field_names(&self, names: &mut Vec<String>) {
+        match self {
+            FilterExpr::Cmp(f) => names.push(f.fname.clone()),
+            FilterExpr::Not(e) | FilterExpr::Group(e) => e.field_names(names),
+            FilterExpr::And(lhs, rhs) | FilterExpr::Or(lhs, rhs) => {
+                lhs.field_names(names);
+                rhs.field_names(names);
+            }
+        }
+    }
+}
 
+/// A record filter is either absent (every record matches) or a boolean expression
+/// tree of field conditions.
 #[derive(Debug)]
 pub struct RecordFilter {
-    pub expr: Vec<FieldFilter>,
+    pub expr: Option<FilterExpr>,
 }
 
 impl RecordFilter {
-    /// Checks if all field names are found in the layout
+    /// Lists every field name referenced anywhere in the expression, empty if there's
+    /// no filter at all.
+    pub fn field_names(&self) -> Vec<String> {
+        let mut names = Vec::new();
+        if let Some(expr) = &self.expr {
+            expr.field_names(&mut names);
+        }
+        names
+    }
+
+    /// Checks if all field names referenced by the expression are found in the layout
     ///
     /// # Examples
     /// ```
     /// use rbf::record::AsciiMode;
-    /// use rbf::layout::{Layout, setup::layout_load_layout_ascii};    
+    /// use rbf::layout::{Layout, setup::layout_load_layout_ascii};
     /// use rbf::filter::recordfilter::RecordFilter;
-    ///     
+    ///
     /// let layout = layout_load_layout_ascii("./tests/test.xml");
     ///
-    /// let filters = RecordFilter::from("W10 = AA;N5 != 20");
+    /// let filters = RecordFilter::from("W10 = AA AND N5 != 20");
     /// filters.check(layout);
     ///
     /// ```
     /// ```,should_panic
     /// use rbf::record::AsciiMode;
-    /// use rbf::layout::{Layout, setup::layout_load_layout_ascii};    
+    /// use rbf::layout::{Layout, setup::layout_load_layout_ascii};
     /// use rbf::filter::recordfilter::RecordFilter;
-    ///     
+    ///
     /// let layout = layout_load_layout_ascii("./tests/test.xml");
     ///
-    /// let filters = RecordFilter::from("FIELD1 = AA;N5 != 20");
+    /// let filters = RecordFilter::from("FIELD1 = AA AND N5 != 20");
     /// filters.check(layout);
     ///
-    /// ```     
+    /// ```
+    ///
+    /// # Panics
+    /// If a referenced field isn't in `layout`. Prefer [`RecordFilter::try_check`]
+    /// when a non-panicking conversion is needed.
     pub fn check<T>(&self, layout: Layout<T>) {
-        for expr in &self.expr {
-            if !layout.contains_field(&expr.fname) {
-                panic!("field name {} is not found in the layout!", expr.fname);
+        self.try_check(layout).expect("field referenced by filter not found in layout");
+    }
+
+    /// Fallible counterpart of [`RecordFilter::check`].
+    pub fn try_check<T>(&self, layout: Layout<T>) -> Result<(), RbfError> {
+        for fname in &self.field_names() {
+            if !layout.contains_field(fname) {
+                return Err(RbfError::UnknownField(fname.clone()));
             }
         }
+        Ok(())
+    }
+}
+
+/// Splits an expression string into tokens: field names and values, the comparison
+/// operators (`= != ~ !~ < <= > >= <>`), the keywords `AND`/`OR`/`NOT` (or their symbolic
+/// spellings `&& || !`), `;` (sugar for top-level `AND`), and parentheses. Parentheses
+/// and operators need not be surrounded by whitespace.
+fn tokenize(input: &str) -> Vec<String> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    // whether `chars[i]` starts a 2-char operator
+    fn two_char_op(chars: &[char], i: usize) -> Option<&'static str> {
+        if i + 1 >= chars.len() {
+            return None;
+        }
+        match (chars[i], chars[i + 1]) {
+            ('!', '=') => Some("!="),
+            ('!', '~') => Some("!~"),
+            ('<', '>') => Some("<>"),
+            ('<', '=') => Some("<="),
+            ('>', '=') => Some(">="),
+            ('&', '&') => Some("&&"),
+            ('|', '|') => Some("||"),
+            _ => None,
+        }
+    }
+
+    while i < chars.len() {
+        let c = chars[i];
+
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '(' || c == ')' || c == ';' {
+            tokens.push(c.to_string());
+            i += 1;
+        } else if let Some(op) = two_char_op(&chars, i) {
+            tokens.push(op.to_string());
+            i += 2;
+        } else if c == '=' || c == '~' || c == '<' || c == '>' || c == '!' {
+            tokens.push(c.to_string());
+            i += 1;
+        } else {
+            let start = i;
+            while i < chars.len()
+                && !chars[i].is_whitespace()
+                && chars[i] != '('
+                && chars[i] != ')'
+                && chars[i] != ';'
+                && chars[i] != '='
+                && chars[i] != '~'
+                && chars[i] != '<'
+                && chars[i] != '>'
+                && chars[i] != '!'
+                && two_char_op(&chars, i).is_none()
+            {
+                i += 1;
+            }
+            tokens.push(chars[start..i].iter().collect());
+        }
+    }
+
+    tokens
+}
+
+/// Small recursive-descent parser implementing:
+///
+/// ```text
+/// top     := expr (';' expr)*
+/// expr    := or_expr
+/// or_expr := and_expr ((OR | '||') and_expr)*
+/// and_expr:= unary ((AND | '&&') unary)*
+/// unary   := (NOT | '!') unary | primary
+/// primary := '(' expr ')' | field op value
+/// ```
+struct Parser {
+    tokens: Vec<String>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&str> {
+        self.tokens.get(self.pos).map(String::as_str)
+    }
+
+    fn next(&mut self) -> String {
+        let tok = self.tokens[self.pos].clone();
+        self.pos += 1;
+        tok
+    }
+
+    /// Parses the whole input: `;`-separated expressions ANDed together, with no
+    /// token left over.
+    fn parse_top(&mut self) -> Result<FilterExpr, RbfError> {
+        let mut lhs = self.parse_expr()?;
+        while self.peek() == Some(";") {
+            self.next();
+            let rhs = self.parse_expr()?;
+            lhs = FilterExpr::And(Box::new(lhs), Box::new(rhs));
+        }
+        if let Some(tok) = self.peek() {
+            return Err(RbfError::Malformed {
+                context: "record filter expression".to_string(),
+                reason: format!("unexpected token <{}>", tok),
+            });
+        }
+        Ok(lhs)
+    }
+
+    fn parse_expr(&mut self) -> Result<FilterExpr, RbfError> {
+        self.parse_or()
+    }
+
+    fn parse_or(&mut self) -> Result<FilterExpr, RbfError> {
+        let mut lhs = self.parse_and()?;
+        while self.peek() == Some("OR") || self.peek() == Some("||") {
+            self.next();
+            let rhs = self.parse_and()?;
+            lhs = FilterExpr::Or(Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_and(&mut self) -> Result<FilterExpr, RbfError> {
+        let mut lhs = self.parse_unary()?;
+        while self.peek() == Some("AND") || self.peek() == Some("&&") {
+            self.next();
+            let rhs = self.parse_unary()?;
+            lhs = FilterExpr::And(Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_unary(&mut self) -> Result<FilterExpr, RbfError> {
+        if self.peek() == Some("NOT") || self.peek() == Some("!") {
+            self.next();
+            return Ok(FilterExpr::Not(Box::new(self.parse_unary()?)));
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<FilterExpr, RbfError> {
+        if self.peek() == Some("(") {
+            self.next();
+            let inner = self.parse_expr()?;
+            match self.peek() {
+                Some(")") => {
+                    self.next();
+                }
+                _ => {
+                    return Err(RbfError::Malformed {
+                        context: "record filter expression".to_string(),
+                        reason: "unbalanced parenthesis".to_string(),
+                    })
+                }
+            }
+            return Ok(FilterExpr::Group(Box::new(inner)));
+        }
+
+        // bare comparison: field op value
+        let fname = self.expect_token()?;
+        let op = self.expect_token()?;
+        let value = self.expect_token()?;
+        Ok(FilterExpr::Cmp(FieldFilter::try_new(&fname, &op, &value)?))
+    }
+
+    /// Consumes and returns the next token, or a `Malformed` error if the input ran
+    /// out where a field/operator/value was expected.
+    fn expect_token(&mut self) -> Result<String, RbfError> {
+        if self.pos >= self.tokens.len() {
+            return Err(RbfError::Malformed {
+                context: "record filter expression".to_string(),
+                reason: "unexpected end of input".to_string(),
+            });
+        }
+        Ok(self.next())
     }
 }
 
@@ -50,21 +293,47 @@ impl RecordFilter {
 ///
 /// ```
 /// use rbf::filter::recordfilter::RecordFilter;
-///    
-/// let filters = RecordFilter::from("FIELD1 = 10;FIELD2 != 20; FIELD3 ~ ^#");
-/// assert_eq!(format!("{}", filters.expr[0]), "FIELD1=10");
-/// assert_eq!(format!("{}", filters.expr[1]), "FIELD2!=20");
-/// assert_eq!(format!("{}", filters.expr[2]), "FIELD3~^#");
+///
+/// let filters = RecordFilter::from("FIELD1 = 10 AND FIELD2 != 20 AND FIELD3 ~ ^#");
 /// ```
+///
+/// The degenerate case of a single condition still works:
+/// ```
+/// use rbf::filter::recordfilter::{RecordFilter, FilterExpr};
+///
+/// let filters = RecordFilter::from("FIELD1 = 10");
+/// assert!(match filters.expr { Some(FilterExpr::Cmp(_)) => true, _ => false });
+/// ```
+///
+/// `;` is sugar for top-level `AND`, and `&&`/`||`/`!` are accepted alongside the
+/// `AND`/`OR`/`NOT` keywords:
+/// ```
+/// use rbf::filter::recordfilter::RecordFilter;
+///
+/// let filters = RecordFilter::from("FIELD1 = 10; FIELD2 != 20");
+/// let filters = RecordFilter::from("(FIELD1 = 10 || FIELD1 = 20) && !(FIELD2 ~ ^BAD)");
+/// let filters = RecordFilter::from("FIELD1 <= 10 AND FIELD2 >= 20");
+/// ```
+///
+/// # Panics
+/// If `op` doesn't parse as a valid filter expression. Prefer `TryFrom` when a
+/// non-panicking conversion is needed.
 impl<'a> From<&'a str> for RecordFilter {
     fn from(op: &'a str) -> RecordFilter {
-        let mut vec: Vec<FieldFilter> = Vec::new();
+        RecordFilter::try_from(op).expect("unable to parse record filter expression")
+    }
+}
 
-        // split according to delimiter
-        for expr in op.split(FIELD_FILTER_DELIMITER) {
-            vec.push(FieldFilter::from(expr));
-        }
+/// Fallible conversion from a string ref: the non-panicking counterpart of
+/// `From<&str> for RecordFilter`.
+impl<'a> TryFrom<&'a str> for RecordFilter {
+    type Error = RbfError;
+
+    fn try_from(op: &'a str) -> Result<RecordFilter, RbfError> {
+        let tokens = tokenize(op);
+        let mut parser = Parser { tokens, pos: 0 };
+        let expr = parser.parse_top()?;
 
-        RecordFilter { expr: vec }
+        Ok(RecordFilter { expr: Some(expr) })
     }
 }
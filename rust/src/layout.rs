@@ -32,14 +32,15 @@ use std::fs::File;
 //use std::error::Error;
 use std::collections::HashMap;
 use std::io::BufReader;
+use std::path::Path;
 use std::rc::Rc;
 
 use xml::reader::{EventReader, XmlEvent};
 
-use crate::field::Field;
+use crate::field::{Field, FieldCreationType, Subfield};
 use crate::mapper::RecordMapper;
 use crate::record::Record;
-use crate::types::fieldtype::FieldType;
+use crate::types::fieldtype::{FieldType, Justify};
 //use util::into_field_list;
 use crate::error::{RbfError, Result};
 
@@ -88,6 +89,72 @@ fn as_hash(attributes: &Vec<OwnedAttribute>) -> HashMap<&str, &str> {
     h
 }
 
+/// A single problem found while walking a record's fields in order and computing
+/// their byte offsets, similar to what a compiler would flag when laying out a
+/// struct's fields. See [`Layout::is_valid`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum OffsetIssue {
+    /// `second`'s range starts before `first`'s ends
+    Overlap {
+        first: String,
+        second: String,
+        at: usize,
+    },
+    /// there are unaccounted-for bytes between `after` and `before`
+    Gap {
+        after: String,
+        before: String,
+        len: usize,
+    },
+    /// `field`'s upper offset falls at or beyond the record's length
+    OutOfBounds {
+        field: String,
+        offset: usize,
+        rec_length: usize,
+    },
+    /// there are unaccounted-for bytes between `after` (the record's last field)
+    /// and the record's declared length
+    TrailingGap { after: String, len: usize },
+}
+
+/// Offset diagnostic for a single record, as computed by [`Layout::is_valid`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordOffsets {
+    pub record: String,
+    pub issues: Vec<OffsetIssue>,
+}
+
+/// Outcome of [`Layout::is_valid`]: one [`RecordOffsets`] diagnostic per record in
+/// the layout.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayoutOffsets {
+    pub records: Vec<RecordOffsets>,
+}
+
+impl LayoutOffsets {
+    /// `true` if no record reported any overlap, gap or out-of-bounds field.
+    pub fn is_valid(&self) -> bool {
+        self.records.iter().all(|r| r.issues.is_empty())
+    }
+}
+
+/// Pads (or truncates) `value` out to `length` characters, using `pad_char` on the
+/// side indicated by `justify`. Used by [`Layout::write_record`].
+fn pad(value: &str, length: usize, pad_char: char, justify: &Justify) -> String {
+    let count = value.chars().count();
+
+    if count >= length {
+        return value.chars().take(length).collect();
+    }
+
+    let filler: String = std::iter::repeat(pad_char).take(length - count).collect();
+
+    match justify {
+        Justify::Left => format!("{}{}", value, filler),
+        Justify::Right => format!("{}{}", filler, value),
+    }
+}
+
 impl<T> Layout<T> {
     /// Reads the XML layout file to create record and field structs.
     ///
@@ -97,13 +164,20 @@ impl<T> Layout<T> {
     ///
     ///
     /// # Panics
-    /// If `xml_file` could not be read   
-    pub fn new(xml_file: &str) -> Result<Layout<T>> {
-        // try to open xml_file
-        let file = match File::open(&xml_file) {
-            Ok(file) => BufReader::new(file),
-            Err(e) => return Err(RbfError::ErrorOpeningLayoutFile(xml_file.to_string(), e)),
-        };
+    /// If a mandatory XML attribute is missing or malformed. An `xml_file` that can't be
+    /// opened or parsed is returned as an `Err` instead.
+    pub fn new<P: AsRef<Path>>(xml_file: P) -> Result<Layout<T>> {
+        let xml_file = xml_file.as_ref();
+        // only used for error messages and the `xml_file` field, which stay
+        // displayable strings; the file itself is opened from the `Path` directly so
+        // a non-UTF-8 path still opens correctly
+        let display_name = xml_file.display().to_string();
+
+        // try to open xml_file. `File::open` keeps its own io::Error, which we fold into
+        // the file name to give a more useful message than a bare `From<io::Error>` could
+        let file = File::open(xml_file)
+            .map_err(|e| RbfError::ErrorOpeningLayoutFile(display_name.clone(), e))?;
+        let file = BufReader::new(file);
 
         // define hash to hold fieldtypes
         let mut ftypes: HashMap<String, Rc<FieldType>> = HashMap::new();
@@ -165,6 +239,19 @@ impl<T> Layout<T> {
                                 ft.set_pattern(&v);
                             }
 
+                            // padding used when writing a value of this type back out
+                            if attr.get("pad-char").is_some() || attr.get("justify").is_some() {
+                                let pad_char = attr
+                                    .get("pad-char")
+                                    .and_then(|v| v.chars().next())
+                                    .unwrap_or(' ');
+                                let justify = match attr.get("justify") {
+                                    Some(&"right") => Justify::Right,
+                                    _ => Justify::Left,
+                                };
+                                ft.set_padding(pad_char, justify);
+                            }
+
                             // finally insert field type
                             ftypes.insert(ft_name.to_string(), Rc::new(ft));
                         }
@@ -185,10 +272,21 @@ impl<T> Layout<T> {
 
                             // add new record
                             //rec_list.push(Record::new(last_rec_name, rec_type, rec_length))
-                            rec_map.insert(
-                                rec_name.to_string(),
-                                Record::<T>::new(rec_name, rec_desc, rec_length),
-                            );
+                            let mut rec = Record::<T>::new(rec_name, rec_desc, rec_length);
+
+                            // directory-based records (see `DirectoryMode`) carry their own
+                            // leader width and terminator bytes instead of a fixed length
+                            if let Some(v) = attr.get("leader-width") {
+                                rec.leader_width = v.parse::<usize>().unwrap();
+                            }
+                            if let Some(v) = attr.get("field-terminator") {
+                                rec.field_terminator = u8::from_str_radix(v, 16).unwrap();
+                            }
+                            if let Some(v) = attr.get("record-terminator") {
+                                rec.record_terminator = u8::from_str_radix(v, 16).unwrap();
+                            }
+
+                            rec_map.insert(rec_name.to_string(), rec);
                         }
                         "field" => {
                             // name and description are mandatory
@@ -203,13 +301,36 @@ impl<T> Layout<T> {
                                 Some(ft) => ft,
                                 None => {
                                     return Err(RbfError::ErrorLayoutNoFieldType(
-                                        xml_file.to_string(),
+                                        display_name.clone(),
                                         f_name.to_string(),
                                         f_type,
                                     ));
                                 }
                             };
 
+                            // a composite field (e.g. MARC-style) packs several subfields into
+                            // its raw value, separated by this delimiter; <subfield> children
+                            // met next attach themselves to the field just pushed
+                            let subfield_delimiter =
+                                attr.get("subfield-delimiter").and_then(|v| v.chars().next());
+
+                            // an all-blank value for this field means it's absent rather than
+                            // a value failing its type's pattern (see `Field::is_present`)
+                            let optional = attr.get("optional").map(|v| *v == "true").unwrap_or(false);
+
+                            // a directory-based field (see `DirectoryMode`) is resolved by
+                            // tag rather than by a static length or offset
+                            if let Some(tag) = attr.get("tag") {
+                                let rec = rec_map.get_mut(&last_rec_name).unwrap();
+                                rec.push(Field::from_tag(f_name, f_desc, &ft, tag));
+                                let last = rec.flist.last_mut().unwrap();
+                                if let Some(delim) = subfield_delimiter {
+                                    last.subfield_delimiter = Some(delim);
+                                }
+                                last.optional = optional;
+                                continue;
+                            }
+
                             // length could be present or Not
                             let f_length = match attr.get("length") {
                                 Some(length) => length.parse::<usize>().unwrap(),
@@ -232,32 +353,58 @@ impl<T> Layout<T> {
                                 };
 
                                 // add Field into the last created record
-                                rec_map
-                                    .get_mut(&last_rec_name)
-                                    .unwrap()
-                                    .push(Field::from_offset(
-                                        f_name,
-                                        f_desc,
-                                        &ft,
-                                        f_lower_offset,
-                                        f_upper_offset,
-                                    ));
+                                let rec = rec_map.get_mut(&last_rec_name).unwrap();
+                                rec.push(Field::from_offset(
+                                    f_name,
+                                    f_desc,
+                                    &ft,
+                                    f_lower_offset,
+                                    f_upper_offset,
+                                ));
+                                let last = rec.flist.last_mut().unwrap();
+                                if let Some(delim) = subfield_delimiter {
+                                    last.subfield_delimiter = Some(delim);
+                                }
+                                last.optional = optional;
                             }
                             // here, length is not null
                             else {
                                 // add Field into the last created record
-                                rec_map
-                                    .get_mut(&last_rec_name)
-                                    .unwrap()
-                                    .push(Field::from_length(f_name, f_desc, &ft, f_length));
+                                let rec = rec_map.get_mut(&last_rec_name).unwrap();
+                                rec.push(Field::from_length(f_name, f_desc, &ft, f_length));
+                                let last = rec.flist.last_mut().unwrap();
+                                if let Some(delim) = subfield_delimiter {
+                                    last.subfield_delimiter = Some(delim);
+                                }
+                                last.optional = optional;
                             }
                         }
+                        "subfield" => {
+                            // mandatory: name of the subfield and the single-character
+                            // identifier that introduces it in the raw value
+                            let sf_name = attr.get("name").unwrap();
+                            let sf_id = attr.get("identifier").unwrap().chars().next().unwrap();
+
+                            // attaches to whichever field was last pushed into the record
+                            // currently being defined
+                            rec_map
+                                .get_mut(&last_rec_name)
+                                .unwrap()
+                                .flist
+                                .last_mut()
+                                .unwrap()
+                                .subfields
+                                .push(Subfield {
+                                    name: sf_name.to_string(),
+                                    identifier: sf_id,
+                                });
+                        }
                         _ => (),
                     }
                     //println!("{} {:?}", name, attributes);
                 }
                 Err(e) => {
-                    return Err(RbfError::ErrorReadingLayoutFile(xml_file.to_string(), e));
+                    return Err(RbfError::ErrorReadingLayoutFile(display_name.clone(), e));
                     //break;
                 }
                 _ => {}
@@ -265,7 +412,7 @@ impl<T> Layout<T> {
         }
 
         Ok(Layout {
-            xml_file: xml_file.to_string(),
+            xml_file: display_name,
             rec_length: rec_length,
             version: version,
             description: description,
@@ -444,37 +591,215 @@ impl<T> Layout<T> {
         }
     }
 
-    /// Checks whether layout is valid: if `rec_length` is not 0, all records have the same length
-    /// the sum of length all fields (i.e. record length) should match the `rec_length` value.
-    /// If not, each record length should match the declared length
+    /// Checks whether layout is valid: walks each record's fields in order, computes
+    /// a running byte offset for every field (the way a compiler lays out a struct's
+    /// fields), and flags overlapping ranges, gaps between consecutive fields,
+    /// offsets exceeding the record's length (either `rec_length`, if all records
+    /// share one, or the record's own `declared_length`), and a trailing gap after
+    /// the last field that leaves some of the declared length unaccounted for.
+    ///
+    /// Fields resolved dynamically (see [`FieldCreationType::ByTag`]) don't have a
+    /// static offset and are skipped, as is the field immediately following one,
+    /// since there's nothing fixed to compare it against.
     ///
     /// # Examples
     /// ```
     /// use rbf::record::AsciiMode;
-    /// use rbf::layout::{Layout, setup::layout_load_layout_ascii};    
-    ///    
+    /// use rbf::layout::{Layout, setup::layout_load_layout_ascii};
+    ///
     /// let mut layout = layout_load_layout_ascii("./tests/test.xml");
     ///
-    /// assert!(layout.is_valid().0);
+    /// assert!(layout.is_valid().is_valid());
+    /// ```
+    pub fn is_valid(&self) -> LayoutOffsets {
+        let rec_length = self.rec_length;
+
+        let records = self
+            .rec_map
+            .iter()
+            .map(|(_, rec)| {
+                let length = if rec_length != 0 {
+                    rec_length
+                } else {
+                    rec.declared_length
+                };
+
+                let mut issues = Vec::new();
+                let mut prev: Option<&Field> = None;
+
+                for f in rec {
+                    if let FieldCreationType::ByTag = f.creation_type {
+                        prev = None;
+                        continue;
+                    }
+
+                    if let Some(p) = prev {
+                        if f.lower_offset <= p.upper_offset {
+                            issues.push(OffsetIssue::Overlap {
+                                first: p.name.clone(),
+                                second: f.name.clone(),
+                                at: f.lower_offset,
+                            });
+                        } else if f.lower_offset > p.upper_offset + 1 {
+                            issues.push(OffsetIssue::Gap {
+                                after: p.name.clone(),
+                                before: f.name.clone(),
+                                len: f.lower_offset - p.upper_offset - 1,
+                            });
+                        }
+                    }
+
+                    if length != 0 && f.upper_offset + 1 > length {
+                        issues.push(OffsetIssue::OutOfBounds {
+                            field: f.name.clone(),
+                            offset: f.upper_offset,
+                            rec_length: length,
+                        });
+                    }
+
+                    prev = Some(f);
+                }
+
+                // the last field's range might not reach all the way to the
+                // record's declared length, leaving a trailing gap the per-field
+                // checks above never see (there's no "next" field to compare against)
+                if let Some(p) = prev {
+                    if length != 0 && p.upper_offset + 1 < length {
+                        issues.push(OffsetIssue::TrailingGap {
+                            after: p.name.clone(),
+                            len: length - p.upper_offset - 1,
+                        });
+                    }
+                }
+
+                RecordOffsets {
+                    record: rec.name.clone(),
+                    issues,
+                }
+            })
+            .collect();
+
+        LayoutOffsets { records }
+    }
+
+    /// Dumps a `(name, offset, length)` tuple for every field of `rec_name`, in
+    /// field order — a flat layout report similar to rustc's `-Z print-type-sizes`.
+    ///
+    /// # Examples
+    /// ```
+    /// use rbf::record::AsciiMode;
+    /// use rbf::layout::{Layout, setup::layout_load_layout_ascii};
     ///
+    /// let layout = layout_load_layout_ascii("./tests/test.xml");
     ///
+    /// let map = layout.field_offset_map("LL").unwrap();
+    /// assert_eq!(map[0].0, "W1");
+    /// assert_eq!(map[0].1, 0);
+    /// ```
+    pub fn field_offset_map(&self, rec_name: &str) -> Result<Vec<(String, usize, usize)>> {
+        let rec = self
+            .get(rec_name)
+            .ok_or_else(|| RbfError::UnknownRecord(rec_name.to_string()))?;
+
+        Ok(rec
+            .into_iter()
+            .map(|f| (f.name.clone(), f.lower_offset, f.length))
+            .collect())
+    }
+
+    /// Assembles a record of name `rec_name` from `values` (field name -> value),
+    /// in field order, ready to be written to a fixed-width or delimited file.
     ///
-    /// ```       
-    pub fn is_valid(&self) -> (bool, &str, usize, usize) {
-        if self.rec_length != 0 {
-            for (_, rec) in &self.rec_map {
-                if self.rec_length != rec.calculated_length {
-                    return (false, "", self.rec_length, rec.calculated_length);
-                }
+    /// Each value is checked against its field's declared `pattern`, then padded (or
+    /// truncated) out to the field's declared length, using the padding character
+    /// and justification configured on the field's `FieldType` (left-justified,
+    /// blank-padded by default). A field missing from `values` is treated as empty.
+    ///
+    /// # Errors
+    /// `RbfError::UnknownRecord` if `rec_name` isn't in the layout,
+    /// `RbfError::PatternMismatch` if a value doesn't match its field's pattern, and
+    /// `RbfError::LengthMismatch` if the assembled record doesn't match the record's
+    /// declared length.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::collections::HashMap;
+    ///
+    /// use rbf::record::AsciiMode;
+    /// use rbf::layout::{Layout, setup::layout_load_layout_ascii};
+    ///
+    /// let layout = layout_load_layout_ascii("./tests/test.xml");
+    ///
+    /// let mut values: HashMap<&str, &str> = HashMap::new();
+    /// values.insert("W1", "hello");
+    ///
+    /// let line = layout.write_record("LL", &values).unwrap();
+    /// assert_eq!(line.len(), layout.get("LL").unwrap().calculated_length);
+    /// ```
+    pub fn write_record(&self, rec_name: &str, values: &HashMap<&str, &str>) -> Result<String> {
+        let rec = self
+            .get(rec_name)
+            .ok_or_else(|| RbfError::UnknownRecord(rec_name.to_string()))?;
+
+        let mut line = String::with_capacity(rec.calculated_length);
+
+        for f in rec {
+            let value = values.get(f.name.as_str()).copied().unwrap_or("");
+
+            if !f.ftype.pattern.is_match(value) {
+                return Err(RbfError::PatternMismatch {
+                    field: f.name.clone(),
+                    value: value.to_string(),
+                });
             }
+
+            line += &pad(value, f.length, f.ftype.pad_char, &f.ftype.justify);
+        }
+
+        let expected = if self.rec_length != 0 {
+            self.rec_length
         } else {
-            for (_, rec) in &self.rec_map {
-                if rec.declared_length != rec.calculated_length {
-                    return (false, &rec.name, rec.declared_length, rec.calculated_length);
-                }
-            }
+            rec.declared_length
+        };
+
+        if expected != 0 && line.chars().count() != expected {
+            return Err(RbfError::LengthMismatch {
+                record: rec_name.to_string(),
+                expected,
+                actual: line.chars().count(),
+            });
         }
-        (true, "", 0, 0)
+
+        Ok(line)
+    }
+
+    /// Writes a record assembled by [`Layout::write_record`] to `writer`, followed by
+    /// a newline.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::collections::HashMap;
+    ///
+    /// use rbf::record::AsciiMode;
+    /// use rbf::layout::{Layout, setup::layout_load_layout_ascii};
+    ///
+    /// let layout = layout_load_layout_ascii("./tests/test.xml");
+    ///
+    /// let mut values: HashMap<&str, &str> = HashMap::new();
+    /// values.insert("W1", "hello");
+    ///
+    /// let mut out: Vec<u8> = Vec::new();
+    /// layout.write_to(&mut out, "LL", &values).unwrap();
+    /// ```
+    pub fn write_to<W: ::std::io::Write>(
+        &self,
+        writer: &mut W,
+        rec_name: &str,
+        values: &HashMap<&str, &str>,
+    ) -> Result<()> {
+        let line = self.write_record(rec_name, values)?;
+        writeln!(writer, "{}", line)?;
+        Ok(())
     }
 
     /// Sets skip field.
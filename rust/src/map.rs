@@ -0,0 +1,383 @@
+//! Embeds a Lua script to let callers hook into the record pipeline: a record can be
+//! kept or discarded by a Lua predicate function, and a field value can be rewritten
+//! by a Lua transform function. Both hooks are optional: a `ScriptEngine` can be built
+//! with either, or both, function names.
+//!
+//! `ScriptEngine` still talks to Lua through the raw `luacall_*` C FFI layer, loading
+//! a Lua state once with the user's script and calling named global functions with a
+//! string argument, returning a string. [`Mapper`], which computes a record ID from a
+//! line through a Lua function, is instead backed by `mlua`'s safe, high-level
+//! bindings, with no manual FFI ownership juggling.
+//!
+//! # Examples
+//! ```no_run
+//! use rbf::map::ScriptEngine;
+//!
+//! let engine = ScriptEngine::new("./tests/lua/test.lua");
+//!
+//! // keep only records for which "keep_record" returns a non-empty, non-"0" string
+//! assert!(engine.filter_record("keep_record", "AABBAAAAAAAAAAAAAAAAAAAA"));
+//!
+//! // rewrite a field value through the "upper" Lua function
+//! let transformed = engine.transform_field("upper", "aabb");
+//! assert_eq!(transformed, "AABB");
+//! ```
+//!
+//! Gated behind the `lua` Cargo feature: without it, this module (and the C build step
+//! in `build.rs`) is compiled out entirely, so the default build needs neither a Lua
+//! toolchain nor a C compiler.
+//!
+//! This leaves two parallel, architecturally inconsistent ways to call into Lua in
+//! this module: `ScriptEngine`'s raw FFI and [`Mapper`]'s safe `mlua` bindings.
+//! Porting `ScriptEngine` onto `mlua` and retiring the C shim in `build.rs` is
+//! tracked as its own follow-up — not done here, since it's a breaking change to
+//! `ScriptEngine`'s panic-based API that callers (`urr`'s `--lua-filter`/
+//! `--lua-transform`) depend on today.
+//!
+//! [`Mapper`] additionally selects its embedded Lua runtime through one more Cargo
+//! feature, mutually exclusive with the others: `lua51`, `lua52`, `lua53`, `lua54`,
+//! `luajit` or `luau`, forwarded straight to `mlua`'s own feature of the same name.
+//! The FFI/version differences between those runtimes never surface past `mlua`'s
+//! high-level API, so [`Mapper`]'s own code is identical regardless of which one is
+//! active; [`ActiveLuaBackend`] is the one place that names it.
+#![cfg(feature = "lua")]
+
+use std::ffi::CString;
+use std::os::raw::{c_char, c_int};
+
+use crate::error::{RbfError, Result};
+
+extern "C" {
+    fn luacall_initialize() -> c_int;
+    fn luacall_cleanup();
+    fn luacall_loadfile(script: *const c_char) -> c_int;
+    fn luacall_func_string(func: *const c_char, arg: *const c_char, ret: *mut c_char) -> c_int;
+}
+
+const LUA_OK: c_int = 0;
+
+/// Loads a Lua script once and exposes it as a source of per-record and per-field hooks.
+pub struct ScriptEngine {
+    /// path to the loaded Lua script, kept for error messages
+    script: String,
+}
+
+impl ScriptEngine {
+    /// Loads and compiles `script` into the embedded Lua interpreter.
+    ///
+    /// # Panics
+    /// If the Lua interpreter cannot be initialized or the script fails to load.
+    pub fn new(script: &str) -> ScriptEngine {
+        let mut rc: c_int;
+        unsafe {
+            rc = luacall_initialize();
+        }
+        if rc != LUA_OK {
+            panic!("unable to initialize Lua interpreter, rc={}", rc);
+        }
+
+        let c_script = CString::new(script).unwrap();
+        unsafe {
+            rc = luacall_loadfile(c_script.as_ptr());
+        }
+        if rc != LUA_OK {
+            panic!("unable to load Lua script {}, Lua rc={}", script, rc);
+        }
+
+        ScriptEngine {
+            script: script.to_string(),
+        }
+    }
+
+    /// Calls the named Lua function with `arg`, returning its string result.
+    ///
+    /// # Panics
+    /// If the Lua call fails (e.g. `func` is not defined in the script).
+    fn call(&self, func: &str, arg: &str) -> String {
+        let c_func = CString::new(func).unwrap();
+        let c_arg = CString::new(arg).unwrap();
+        let pre_allocated = String::with_capacity(arg.len() + 1);
+        let ret = CString::new(pre_allocated).unwrap();
+
+        // need to transform into a raw pointer to call C
+        let ptr = ret.into_raw();
+
+        let rc: c_int;
+        unsafe {
+            rc = luacall_func_string(c_func.as_ptr(), c_arg.as_ptr(), ptr);
+        }
+        if rc != LUA_OK {
+            panic!(
+                "unable to call Lua function {} from script {}, Lua rc={}",
+                func, self.script, rc
+            );
+        }
+
+        // need to retake ownership
+        let returned_string = unsafe { CString::from_raw(ptr).into_string() };
+        returned_string.unwrap()
+    }
+
+    /// Calls `func` with the raw record line and keeps the record unless the Lua
+    /// function returns an empty string or `"0"`.
+    pub fn filter_record(&self, func: &str, line: &str) -> bool {
+        let result = self.call(func, line);
+        !(result.is_empty() || result == "0")
+    }
+
+    /// Calls `func` with a field's value and returns the rewritten value.
+    pub fn transform_field(&self, func: &str, value: &str) -> String {
+        self.call(func, value)
+    }
+}
+
+/// Cleans up the Lua environment.
+impl Drop for ScriptEngine {
+    fn drop(&mut self) {
+        unsafe {
+            luacall_cleanup();
+        }
+    }
+}
+
+/// Names the embedded Lua runtime a build was compiled against. The rest of the
+/// crate never matches on it: it exists only so callers (and the test matrix in
+/// this module) can tell which of the mutually exclusive `lua51`/`lua52`/`lua53`/
+/// `lua54`/`luajit`/`luau` Cargo features is active.
+pub trait LuaBackend {
+    /// Name of the active backend, e.g. `"lua54"`.
+    fn name() -> &'static str;
+}
+
+/// The [`LuaBackend`] selected by whichever `lua5x`/`luajit`/`luau` Cargo feature
+/// is enabled. Defaults to `"lua54"` when none of them is set explicitly, matching
+/// `mlua`'s own default.
+pub struct ActiveLuaBackend;
+
+impl LuaBackend for ActiveLuaBackend {
+    fn name() -> &'static str {
+        if cfg!(feature = "lua51") {
+            "lua51"
+        } else if cfg!(feature = "lua52") {
+            "lua52"
+        } else if cfg!(feature = "lua53") {
+            "lua53"
+        } else if cfg!(feature = "luajit") {
+            "luajit"
+        } else if cfg!(feature = "luau") {
+            "luau"
+        } else {
+            "lua54"
+        }
+    }
+}
+
+/// Computes a record ID from a raw line via a Lua-defined function: the mapper
+/// function is called with the line and returns the record ID as a string. Unlike
+/// [`ScriptEngine`], which still talks to Lua through the raw `luacall_*` C FFI,
+/// this is backed by `mlua`'s high-level bindings, so the Lua state is an owned
+/// Rust value and a malformed script or a runtime error in the mapper function
+/// surfaces as [`RbfError::Lua`] instead of panicking.
+pub struct Mapper {
+    /// owned Lua interpreter state, loaded once with the mapper script
+    lua: ::mlua::Lua,
+    /// path to the loaded Lua script, kept for error messages
+    script: String,
+    /// name of the global Lua function used as the mapper
+    func: String,
+    /// maximum length (in chars) of the record ID returned by [`Mapper::call`]
+    max_id: usize,
+    /// instruction counter shared with the interrupt callback installed by
+    /// [`Mapper::new_sandboxed`] (always present, unused unless sandboxed); reset to
+    /// 0 at the start of every call so the instruction budget applies per call
+    /// rather than accumulating over the `Mapper`'s whole lifetime
+    steps: ::std::rc::Rc<::std::cell::Cell<u64>>,
+}
+
+impl Mapper {
+    /// Loads and compiles `script`, and checks that `func` is defined in it as a
+    /// global function.
+    ///
+    /// # Errors
+    /// `RbfError::Io` if `script` cannot be read, `RbfError::Lua` if it fails to
+    /// compile or `func` isn't a defined function.
+    pub fn new(script: &str, func: &str, max_id: usize) -> Result<Mapper> {
+        let lua = ::mlua::Lua::new();
+
+        let code = ::std::fs::read_to_string(script)?;
+        lua.load(&code).set_name(script).exec()?;
+
+        lua.globals()
+            .get::<_, ::mlua::Function>(func)
+            .map_err(|_| {
+                RbfError::Lua(::mlua::Error::RuntimeError(format!(
+                    "Lua function {} is not defined in script {}",
+                    func, script
+                )))
+            })?;
+
+        Ok(Mapper {
+            lua,
+            script: script.to_string(),
+            func: func.to_string(),
+            max_id,
+            steps: ::std::rc::Rc::new(::std::cell::Cell::new(0)),
+        })
+    }
+
+    /// Like [`Mapper::new`], but hardens the Lua state for untrusted scripts — a
+    /// layout definition ingested from a third party, say. On the `luau` backend
+    /// this enables Luau's readonly-globals sandbox, so a script can't monkey-patch
+    /// the base library; on every backend it strips the `io`, `os` and `package`
+    /// modules, and installs an interrupt callback that fails the call once it has
+    /// run more than `max_instructions` VM steps, so a runaway mapper on a huge file
+    /// can't hang the reader — the budget applies per call (the step counter resets
+    /// at the start of every [`Mapper::call`]/[`Mapper::call_with_record`]), not
+    /// cumulatively over the `Mapper`'s lifetime. The sandboxed Lua state itself is
+    /// seeded once here and reused across every call, with no per-call re-seeding.
+    ///
+    /// # Errors
+    /// Same as [`Mapper::new`]. A call that later exceeds `max_instructions`
+    /// surfaces as `RbfError::Lua` from [`Mapper::call`], not a panic or an abort.
+    pub fn new_sandboxed(
+        script: &str,
+        func: &str,
+        max_id: usize,
+        max_instructions: u64,
+    ) -> Result<Mapper> {
+        let mapper = Self::new(script, func, max_id)?;
+
+        for module in ["io", "os", "package"] {
+            mapper.lua.globals().set(module, ::mlua::Nil)?;
+        }
+
+        #[cfg(feature = "luau")]
+        mapper.lua.sandbox(true)?;
+
+        let script_name = mapper.script.clone();
+        let steps = mapper.steps.clone();
+        mapper.lua.set_interrupt(move |_| {
+            steps.set(steps.get() + 1);
+            if steps.get() > max_instructions {
+                Err(::mlua::Error::RuntimeError(format!(
+                    "mapper script {} exceeded its {}-instruction budget",
+                    script_name, max_instructions
+                )))
+            } else {
+                Ok(::mlua::VmState::Continue)
+            }
+        });
+
+        Ok(mapper)
+    }
+
+    /// Looks up the mapper's global Lua function, in case it was removed from the
+    /// interpreter's globals after `new` checked it (e.g. by a previous call).
+    fn func(&self) -> Result<::mlua::Function> {
+        self.lua.globals().get(self.func.as_str()).map_err(|_| {
+            RbfError::Lua(::mlua::Error::RuntimeError(format!(
+                "Lua function {} is not defined in script {}",
+                self.func, self.script
+            )))
+        })
+    }
+
+    /// Calls the mapper function with `arg` (the raw record line) and returns the
+    /// record ID it computes, truncated to `max_id` chars.
+    ///
+    /// # Errors
+    /// `RbfError::Lua` if the mapper function isn't defined anymore or raises a
+    /// runtime error.
+    pub fn call(&self, arg: &str) -> Result<String> {
+        self.steps.set(0);
+        let result: String = self.func()?.call(arg)?;
+        Ok(result.chars().take(self.max_id).collect())
+    }
+
+    /// Like [`Mapper::call`], but passes `rec` to the mapper function as structured
+    /// Lua userdata (see [`LuaRecord`]) instead of the raw line, so the script can
+    /// pull already-parsed field values — `rec:field("TYPE")`, `rec:value(n)`,
+    /// `rec:name()` — rather than re-slicing the line by byte offset. This makes a
+    /// mapper script independent of physical field offsets.
+    ///
+    /// # Errors
+    /// `RbfError::Lua` if the mapper function isn't defined anymore or raises a
+    /// runtime error.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use rbf::map::Mapper;
+    /// use rbf::record::{AsciiMode, ReadMode, setup::set_up_by_length};
+    ///
+    /// let mapper = Mapper::new("./tests/lua/test.lua", "map_from_record", 10).unwrap();
+    ///
+    /// let mut rec = set_up_by_length::<AsciiMode>();
+    /// rec.set_value("AAAAAAAAAABBBBBBBBBBCCCCCCCCCCCCCCCCCCCCDDDDDDDDDD");
+    ///
+    /// let id = mapper.call_with_record(&rec).unwrap();
+    /// ```
+    pub fn call_with_record<T>(&self, rec: &crate::record::Record<T>) -> Result<String> {
+        self.steps.set(0);
+        let lua_rec = LuaRecord {
+            name: rec.name.clone(),
+            fields: rec.into_iter().map(|f| (f.name.clone(), f.value().clone())).collect(),
+        };
+
+        let result: String = self.func()?.call(lua_rec)?;
+        Ok(result.chars().take(self.max_id).collect())
+    }
+}
+
+/// A read-only snapshot of a [`crate::record::Record`]'s name and fields, exposed to
+/// Lua mapper scripts (see [`Mapper::call_with_record`]) as userdata with
+/// `rec:name()`, `rec:field(name)` and `rec:value(index)` methods, so scripts can
+/// pull already-parsed field values instead of re-slicing the raw line.
+struct LuaRecord {
+    name: String,
+    fields: Vec<(String, String)>,
+}
+
+impl ::mlua::UserData for LuaRecord {
+    fn add_methods<'lua, M: ::mlua::UserDataMethods<'lua, Self>>(methods: &mut M) {
+        methods.add_method("name", |_, this, ()| Ok(this.name.clone()));
+
+        methods.add_method("field", |_, this, name: String| {
+            Ok(this
+                .fields
+                .iter()
+                .find(|(n, _)| *n == name)
+                .map(|(_, v)| v.clone()))
+        });
+
+        methods.add_method("value", |_, this, index: usize| {
+            Ok(this.fields.get(index).map(|(_, v)| v.clone()))
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ActiveLuaBackend, LuaBackend, Mapper};
+
+    // `Mapper` never touches the Lua runtime's FFI directly, so the same script and
+    // the same assertion hold regardless of which `lua5x`/`luajit`/`luau` feature
+    // this build was compiled with: run this test once per backend in CI (e.g.
+    // `--features lua54`, then `--features luajit`, ...) to get the "test matrix".
+    #[test]
+    fn mapper_call_is_backend_agnostic() {
+        let mapper = Mapper::new("./tests/lua/test.lua", "map1", 2).unwrap();
+        assert_eq!(mapper.call("AABBAAAAAAAAAAAAAAAAAAAA").unwrap(), "AA");
+        assert!(!ActiveLuaBackend::name().is_empty());
+    }
+
+    // the instruction budget must apply per call, not accumulate over the
+    // `Mapper`'s lifetime: a well-behaved script must keep succeeding no matter how
+    // many lines it has already mapped.
+    #[test]
+    fn sandboxed_mapper_budget_does_not_accumulate_across_calls() {
+        let mapper = Mapper::new_sandboxed("./tests/lua/test.lua", "map1", 2, 1_000).unwrap();
+        for _ in 0..10_000 {
+            assert_eq!(mapper.call("AABBAAAAAAAAAAAAAAAAAAAA").unwrap(), "AA");
+        }
+    }
+}
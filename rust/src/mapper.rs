@@ -1,7 +1,10 @@
 use regex::Regex;
 
-/// Convenient conversion from a string ref.
-pub type RecordHasher = Box<Fn(&str) -> String>;
+use crate::error::RbfError;
+
+/// Maps a record line to the ID of its record type. Fallible, since the `"fancy"` mode
+/// can only check its ranges against the actual line length at call time.
+pub type RecordHasher = Box<Fn(&str) -> Result<String, RbfError>>;
 
 pub struct RecordMapper {
     pub func: RecordHasher,
@@ -11,7 +14,7 @@ pub struct RecordMapper {
 impl Default for RecordMapper {
     fn default() -> RecordMapper {
         RecordMapper {
-            func: Box::new(|x: &str| x.to_string()),
+            func: Box::new(|x: &str| Ok(x.to_string())),
         }
     }
 }
@@ -27,12 +30,16 @@ impl Default for RecordMapper {
 ///
 ///  // constant
 ///  let m1 = RecordMapper::new("constant", "FOO");
-///  assert_eq!((m1.func)(s), "FOO");
+///  assert_eq!((m1.func)(s).unwrap(), "FOO");
 ///
 ///  // linear
 ///  let s = "01XX02AAAAAAAAAAAAAAAAAAA";
 ///  let m2 = RecordMapper::new("range", "0..2");
-///  assert_eq!((m2.func)(s), "01");
+///  assert_eq!((m2.func)(s).unwrap(), "01");
+///
+///  // fancy: several non-adjacent ranges, concatenated in order
+///  let m3 = RecordMapper::new("fancy", "0..2,4..6");
+///  assert_eq!((m3.func)(s).unwrap(), "0102");
 /// ```
 ///
 /// ```should_panic
@@ -49,7 +56,7 @@ impl RecordMapper {
                 // in this case, closure is just returning a constant string
                 let dmn = domain.to_string();
                 RecordMapper {
-                    func: Box::new(move |x: &str| dmn.clone()),
+                    func: Box::new(move |x: &str| Ok(dmn.clone())),
                 }
             }
             "range" => {
@@ -62,10 +69,74 @@ impl RecordMapper {
                 );
 
                 RecordMapper {
-                    func: Box::new(move |x: &str| x[range.0..range.1].to_string()),
+                    func: Box::new(move |x: &str| Ok(x[range.0..range.1].to_string())),
+                }
+            }
+            "fancy" => {
+                // a comma-separated list of lo..hi ranges, sliced and joined in order
+                let range_reg = Regex::new(r"(?P<r_inf>\d+)\.\.(?P<r_sup>\d+)").unwrap();
+                let dmn = domain.to_string();
+                let ranges: Vec<(usize, usize)> = range_reg
+                    .captures_iter(&dmn)
+                    .map(|caps_range| {
+                        (
+                            caps_range["r_inf"].parse::<usize>().unwrap(),
+                            caps_range["r_sup"].parse::<usize>().unwrap(),
+                        )
+                    })
+                    .collect();
+
+                RecordMapper {
+                    func: Box::new(move |x: &str| {
+                        if ranges.is_empty() {
+                            return Err(RbfError::Malformed {
+                                context: "fancy mapper domain".to_string(),
+                                reason: format!("no lo..hi range found in <{}>", dmn),
+                            });
+                        }
+
+                        let mut id = String::new();
+                        for &(lo, hi) in &ranges {
+                            if hi < lo || hi > x.len() {
+                                return Err(RbfError::Malformed {
+                                    context: "fancy mapper domain".to_string(),
+                                    reason: format!(
+                                        "range {}..{} is out of bounds for a {}-byte line",
+                                        lo,
+                                        hi,
+                                        x.len()
+                                    ),
+                                });
+                            }
+                            id.push_str(&x[lo..hi]);
+                        }
+                        Ok(id)
+                    }),
+                }
+            }
+            // domain is "path.lua:function_name"; wired to the same safe Lua engine
+            // as `map::Mapper`, so a layout can pick a built-in mapper or a fully
+            // scriptable one through this one construction API.
+            #[cfg(feature = "lua")]
+            "script" => {
+                let mut parts = domain.splitn(2, ':');
+                let script = parts.next().unwrap_or("");
+                let func = parts.next().unwrap_or_else(|| {
+                    panic!(
+                        "script mapper domain must be \"path.lua:function_name\", got <{}>",
+                        domain
+                    )
+                });
+
+                let mapper = crate::map::Mapper::new(script, func, usize::max_value())
+                    .unwrap_or_else(|e| {
+                        panic!("unable to build script mapper from <{}>: {}", domain, e)
+                    });
+
+                RecordMapper {
+                    func: Box::new(move |x: &str| mapper.call(x)),
                 }
             }
-            "fancy" => unimplemented!(),
             _ => panic!("Unknown type pattern {}", mtype),
         }
     }
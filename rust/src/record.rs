@@ -75,8 +75,12 @@ use std::marker::PhantomData;
 use std::ops::{Index, IndexMut};
 use std::slice::{Iter, IterMut};
 
+use serde::de;
+
+use crate::error::RbfError;
 use crate::field::{Field, FieldCreationType};
 use crate::filter::recordfilter::RecordFilter;
+use crate::types::fieldtype::Justify;
 
 /// This allows to define a way to read either pure Ascii data or UTF-8 data. Because the way
 /// of slicing is not the same, it's much more efficient using Ascii.
@@ -170,6 +174,225 @@ impl ReadMode for Record<UTF8Mode> {
     }
 }
 
+/// Directory-based record mode (ISO 2709/MARC style): a record is a fixed-width
+/// `leader_width`-byte leader, followed by a directory of 12-byte entries (a 3-byte
+/// tag, a 4-digit field length and a 5-digit starting position, relative to the
+/// first byte of field data), terminated by `field_terminator`. Field data follows
+/// the directory, each field ending in `field_terminator`, the whole record ending
+/// in `record_terminator`. Fields are declared with [`Field::from_tag`] and matched
+/// against directory entries by tag rather than by a static offset.
+pub struct DirectoryMode;
+
+/// Implement directory-based read mode
+impl ReadMode for Record<DirectoryMode> {
+    /// Sets the record value by walking the leader and directory to locate each
+    /// declared field's data, then slicing it out.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::rc::Rc;
+    ///
+    /// use rbf::types::fieldtype::FieldType;
+    /// use rbf::field::Field;
+    /// use rbf::record::{ReadMode, DirectoryMode, Record};
+    ///
+    /// let ft = Rc::new(FieldType::new("S", "string"));
+    /// let mut rec = Record::<DirectoryMode>::new("BIB", "A MARC-like record", 0);
+    /// rec.leader_width = 5;
+    /// rec.push(Field::from_tag("TITLE", "Title", &ft, "245"));
+    /// rec.push(Field::from_tag("AUTHOR", "Author", &ft, "100"));
+    ///
+    /// // leader (5 bytes) + directory (two 12-byte entries + terminator) + field data,
+    /// // each field's length in the directory covers its data plus the terminator byte
+    /// let leader = "00000";
+    /// let directory = format!("{}{}{}", "245000500000", "100000600005", '\u{1E}');
+    /// let data = format!("Dune{}Frank{}", '\u{1E}', '\u{1E}');
+    /// let raw = format!("{}{}{}{}", leader, directory, data, '\u{1D}');
+    ///
+    /// rec.set_value(&raw);
+    /// assert_eq!(rec.get_value("TITLE"), "Dune");
+    /// assert_eq!(rec.get_value("AUTHOR"), "Frank");
+    /// ```
+    fn set_value(&mut self, value: &str) {
+        let bytes = value.as_bytes();
+
+        if bytes.len() < self.leader_width {
+            return;
+        }
+
+        // record terminator required; directory offsets are meaningless without it
+        if bytes.last() != Some(&self.record_terminator) {
+            return;
+        }
+
+        // walk the directory: 12-byte entries (3-byte tag, 4-digit length, 5-digit
+        // start), terminated by `field_terminator`
+        let mut entries: Vec<(&str, usize, usize)> = Vec::new();
+        let mut pos = self.leader_width;
+
+        while pos < bytes.len() && bytes[pos] != self.field_terminator {
+            if pos + 12 > bytes.len() {
+                break;
+            }
+
+            // directory entries are meant to be plain ASCII digits, but a
+            // corrupt/hostile record could still land one of these computed
+            // offsets mid-character; bail out of the walk rather than let the
+            // slice below panic on a non-UTF-8-boundary index
+            if !value.is_char_boundary(pos)
+                || !value.is_char_boundary(pos + 3)
+                || !value.is_char_boundary(pos + 7)
+                || !value.is_char_boundary(pos + 12)
+            {
+                break;
+            }
+
+            let tag = &value[pos..pos + 3];
+            let flen = value[pos + 3..pos + 7].parse::<usize>().unwrap_or(0);
+            let fstart = value[pos + 7..pos + 12].parse::<usize>().unwrap_or(0);
+
+            entries.push((tag, flen, fstart));
+            pos += 12;
+        }
+
+        // skip the directory's field terminator
+        let field_data_base = pos + 1;
+
+        for f in &mut self.flist {
+            if let Some((_, flen, fstart)) = entries.iter().find(|(tag, _, _)| *tag == f.tag) {
+                let start = field_data_base + fstart;
+                let end = (start + flen).min(value.len());
+
+                if start > end {
+                    continue;
+                }
+
+                // a directory entry whose (start, end) lands mid-character describes
+                // this field's data incorrectly; skip it rather than panic
+                if !value.is_char_boundary(start) || !value.is_char_boundary(end) {
+                    continue;
+                }
+
+                let mut slice = &value[start..end];
+                if slice.ends_with(self.field_terminator as char) {
+                    slice = &slice[..slice.len() - 1];
+                }
+
+                // the directory, not the layout, is authoritative for where this
+                // field actually landed in this particular record instance
+                f.length = slice.chars().count();
+                f.lower_offset = start;
+                f.upper_offset = start + f.length.saturating_sub(1);
+
+                f.set_value(slice);
+            }
+        }
+    }
+}
+
+/// Reads mainframe fixed-width files: raw bytes sliced by each field's byte offset/length,
+/// then decoded through a single-byte EBCDIC code page (see [`crate::codepage`]) into the
+/// field's `String` value. Unlike [`AsciiMode`]/[`UTF8Mode`], the input isn't assumed to be
+/// valid UTF-8, so this mode is read through [`ByteReadMode`] instead of [`ReadMode`].
+pub struct EbcdicMode;
+
+/// Byte-oriented counterpart of [`ReadMode`], for modes like [`EbcdicMode`] whose raw input
+/// can't be represented as a `&str`.
+pub trait ByteReadMode {
+    fn set_value(&mut self, value: &[u8]);
+}
+
+impl ByteReadMode for Record<EbcdicMode> {
+    /// Sets the record value by slicing `value` per field offset/length and decoding each
+    /// slice through `self.code_page` (set with [`Record::set_code_page`]).
+    ///
+    /// # Examples
+    /// ```
+    /// use std::rc::Rc;
+    ///
+    /// use rbf::codepage::cp037;
+    /// use rbf::types::fieldtype::FieldType;
+    /// use rbf::field::Field;
+    /// use rbf::record::{ByteReadMode, EbcdicMode, Record};
+    ///
+    /// let ft = Rc::new(FieldType::new("S", "string"));
+    /// let mut rec = Record::<EbcdicMode>::new("REC", "An EBCDIC record", 6);
+    /// rec.set_code_page(cp037());
+    /// rec.push(Field::from_length("FIELD1", "Description for field 1", &ft, 3));
+    /// rec.push(Field::from_length("FIELD2", "Description for field 2", &ft, 3));
+    ///
+    /// // "ABC" and "123" encoded as CP037 bytes
+    /// let bytes = [0xC1, 0xC2, 0xC3, 0xF1, 0xF2, 0xF3];
+    /// rec.set_value(&bytes);
+    ///
+    /// assert_eq!(rec[0].value(), "ABC");
+    /// assert_eq!(rec[1].value(), "123");
+    /// ```
+    fn set_value(&mut self, value: &[u8]) {
+        let code_page = match &self.code_page {
+            Some(table) => table,
+            None => return,
+        };
+
+        for f in &mut self.flist {
+            let end = (f.upper_offset + 1).min(value.len());
+            if f.lower_offset >= end {
+                continue;
+            }
+
+            let decoded: String = value[f.lower_offset..end]
+                .iter()
+                .map(|b| code_page[*b as usize])
+                .collect();
+            f.set_value(&decoded);
+        }
+    }
+}
+
+/// Reads fixed-width files without assuming valid UTF-8, like csv's `ByteRecord`: raw bytes
+/// are sliced by each field's byte offset/length and kept untouched in [`Field::raw_bytes`]
+/// (see [`Field::set_value_bytes`]), rather than panicking or mangling them as a `String`.
+/// Call [`Field::value_lossy`] on a field to get a string out of it on demand. Like
+/// [`EbcdicMode`], this mode is read through [`ByteReadMode`] instead of [`ReadMode`].
+pub struct RawMode;
+
+impl ByteReadMode for Record<RawMode> {
+    /// Sets the record value by slicing `value` per field offset/length and storing each
+    /// slice as-is via [`Field::set_value_bytes`].
+    ///
+    /// # Examples
+    /// ```
+    /// use std::rc::Rc;
+    ///
+    /// use rbf::types::fieldtype::FieldType;
+    /// use rbf::field::Field;
+    /// use rbf::record::{ByteReadMode, RawMode, Record};
+    ///
+    /// let ft = Rc::new(FieldType::new("S", "string"));
+    /// let mut rec = Record::<RawMode>::new("REC", "A byte-preserving record", 6);
+    /// rec.push(Field::from_length("FIELD1", "Description for field 1", &ft, 3));
+    /// rec.push(Field::from_length("FIELD2", "Description for field 2", &ft, 3));
+    ///
+    /// // 0xFF is not valid UTF-8 on its own
+    /// let bytes = [b'A', b'B', b'C', b'D', 0xFF, b'E'];
+    /// rec.set_value(&bytes);
+    ///
+    /// assert_eq!(rec[0].as_bytes(), b"ABC");
+    /// assert_eq!(rec[1].as_bytes(), &[b'D', 0xFF, b'E']);
+    /// assert_eq!(rec[1].value_lossy(), "D\u{FFFD}E");
+    /// ```
+    fn set_value(&mut self, value: &[u8]) {
+        for f in &mut self.flist {
+            let end = (f.upper_offset + 1).min(value.len());
+            if f.lower_offset >= end {
+                continue;
+            }
+
+            f.set_value_bytes(&value[f.lower_offset..end]);
+        }
+    }
+}
+
 /// Macro which builds a vector of Record data fields.
 #[macro_export]
 macro_rules! vector_of {
@@ -179,6 +402,23 @@ macro_rules! vector_of {
     }};
 }
 
+/// Pads (or truncates) `value` out to `length` characters, using `pad_char` on the
+/// side indicated by `justify`. Used by [`Record::to_line`].
+fn pad(value: &str, length: usize, pad_char: char, justify: &Justify) -> String {
+    let count = value.chars().count();
+
+    if count >= length {
+        return value.chars().take(length).collect();
+    }
+
+    let filler: String = std::iter::repeat(pad_char).take(length - count).collect();
+
+    match justify {
+        Justify::Left => format!("{}{}", value, filler),
+        Justify::Right => format!("{}{}", filler, value),
+    }
+}
+
 #[derive(Clone)]
 pub struct Record<T> {
     /// Record name
@@ -191,6 +431,18 @@ pub struct Record<T> {
     pub flist: Vec<Field>,
     /// Sum of all field lengths
     pub calculated_length: usize,
+    /// Width in bytes of the leader preceding the directory, only used by
+    /// [`DirectoryMode`]; `0` means the record isn't directory-based.
+    pub leader_width: usize,
+    /// Byte marking the end of the directory and the end of each field's data,
+    /// only used by [`DirectoryMode`]. Defaults to `0x1E` (ISO 2709 field terminator).
+    pub field_terminator: u8,
+    /// Byte marking the end of the whole record, only used by [`DirectoryMode`].
+    /// Defaults to `0x1D` (ISO 2709 record terminator).
+    pub record_terminator: u8,
+    /// Byte -> char lookup table, only used by [`EbcdicMode`]. `None` means the record
+    /// isn't EBCDIC-encoded.
+    pub code_page: Option<[char; 256]>,
     /// Reader mode struct, just a place holder
     pub reader_mode: PhantomData<T>,
 }
@@ -226,10 +478,20 @@ impl<T> Record<T> {
             declared_length: length,
             flist: Vec::new(),
             calculated_length: 0,
+            leader_width: 0,
+            field_terminator: 0x1E,
+            record_terminator: 0x1D,
+            code_page: None,
             reader_mode: PhantomData,
         }
     }
 
+    /// Sets the EBCDIC code page used by [`ByteReadMode::set_value`] when `T` is
+    /// [`EbcdicMode`] (see [`crate::codepage::cp037`]).
+    pub fn set_code_page(&mut self, code_page: [char; 256]) {
+        self.code_page = Some(code_page);
+    }
+
     /// Adds a Field structure to the end of the record.
     ///
     /// # Examples
@@ -270,6 +532,10 @@ impl<T> Record<T> {
                 // now length is the greastest bound value
                 self.calculated_length = field.upper_offset + 1;
             }
+            FieldCreationType::ByTag => {
+                // resolved dynamically against the directory at parse time: no
+                // static offset/length to account for here
+            }
         };
 
         // get last field having the same name (if any)
@@ -296,11 +562,19 @@ impl<T> Record<T> {
     /// let mut rec = set_up_by_length::<AsciiMode>();
     ///
     /// assert!(rec.contains_field("FIELD1"));
-    /// assert!(!rec.contains_field("FOO"));    
-    ///    
-    /// ```     
+    /// assert!(!rec.contains_field("FOO"));
+    ///
+    /// ```
     pub fn contains_field(&self, fname: &str) -> bool {
-        self.flist.iter().any(|f| f.name == fname)
+        self.flist
+            .iter()
+            .any(|f| f.name == fname || f.subfields.iter().any(|s| s.name == fname))
+    }
+
+    /// Returns the value of a subfield named `fname`, wherever it's declared among
+    /// this record's composite fields. See [`Field::subfield`].
+    pub fn get_subfield(&self, fname: &str) -> Option<&str> {
+        self.flist.iter().find_map(|f| f.subfield(fname))
     }
 
     /// Returns the number of fields in the record.
@@ -537,22 +811,395 @@ impl<T> Record<T> {
         }
     }
 
-    /// Checks if record value matches combined field filter
+    /// Checks if record value matches the (possibly composite) record filter
     pub fn is_filter_matched(&self, filter: &RecordFilter) -> bool {
-        let mut result = true;
-
-        // check each of the record filters
-        for f in &filter.expr {
-            // get field value if any
-            let fields = match self.get(&f.fname) {
-                Some(f) => f,
-                None => continue,
+        match &filter.expr {
+            Some(expr) => expr.eval(self),
+            None => true,
+        }
+    }
+
+    /// Converts the record to a JSON object, one entry per field keyed by its name,
+    /// each value parsed and coerced through the field's declared `BaseType`. The first
+    /// field of a given name is keyed by that name; each later field sharing it (see
+    /// `multiplicity`) is keyed by its unique `id` instead (e.g. `FIELD21`), so
+    /// duplicates don't overwrite each other.
+    ///
+    /// # Examples
+    /// ```
+    /// use rbf::record::{AsciiMode, ReadMode, setup::set_up_by_length};
+    ///
+    /// let mut rec = set_up_by_length::<AsciiMode>();
+    /// rec.set_value("        42        17                 100         7");
+    ///
+    /// let json = rec.to_json().unwrap();
+    /// assert_eq!(json["FIELD1"], 42);
+    /// assert_eq!(json["FIELD21"], 7);
+    /// ```
+    pub fn to_json(&self) -> Result<::serde_json::Value, RbfError> {
+        let mut map = ::serde_json::Map::with_capacity(self.flist.len());
+
+        for f in &self.flist {
+            let key = if f.multiplicity == 0 {
+                f.name.clone()
+            } else {
+                f.id.clone()
             };
+            map.insert(key, f.ftype.base_type.to_json(f.value())?);
+        }
+
+        Ok(::serde_json::Value::Object(map))
+    }
+
+    /// Deserializes this record's fields into a typed struct `U`, analogous to csv's
+    /// `ByteRecord::deserialize`: each field of `U` is looked up by name among this
+    /// record's fields (honoring `#[serde(rename = "...")]`) and its trimmed value
+    /// parsed into the destination type (`i64`, `f64`, `bool`, `String`, `Option<_>`).
+    /// A field name declared more than once (see `multiplicity`, e.g. the two
+    /// `FIELD2`s in `setup::set_up_by_length`) is exposed as a sequence, so a struct
+    /// field typed `Vec<_>` collects every occurrence in declaration order.
+    ///
+    /// Returns a typed [`RbfError::Deserialization`] on a missing field or a value
+    /// that doesn't parse into its destination type, rather than panicking the way
+    /// [`Record::get_value`] does on an unknown field name.
+    ///
+    /// # Examples
+    /// ```
+    /// use serde::Deserialize;
+    /// use rbf::record::{AsciiMode, ReadMode, setup::set_up_by_length};
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Row {
+    ///     #[serde(rename = "FIELD1")]
+    ///     field1: i64,
+    ///     #[serde(rename = "FIELD2")]
+    ///     field2: Vec<String>,
+    ///     #[serde(rename = "FIELD3")]
+    ///     field3: String,
+    /// }
+    ///
+    /// let mut rec = set_up_by_length::<AsciiMode>();
+    /// rec.set_value("        42BBBBBBBBBBCCCCCCCCCCCCCCCCCCCCDDDDDDDDDD");
+    ///
+    /// let row: Row = rec.deserialize().unwrap();
+    /// assert_eq!(row.field1, 42);
+    /// assert_eq!(row.field2, vec!["BBBBBBBBBB".to_string(), "DDDDDDDDDD".to_string()]);
+    /// assert_eq!(row.field3, "CCCCCCCCCCCCCCCCCCCC");
+    /// ```
+    pub fn deserialize<U: ::serde::de::DeserializeOwned>(&self) -> Result<U, RbfError> {
+        U::deserialize(RecordDeserializer::new(&self.flist))
+    }
+
+    /// Serializes the record's current field values back into a line, the write-back
+    /// counterpart of [`ReadMode::set_value`]: each field's raw value is padded out to its
+    /// declared length using its `FieldType`'s pad character and justification
+    /// (space/left-justified by default, see [`crate::types::fieldtype::FieldType::set_padding`]),
+    /// and for a by-offset record (see [`Field::from_offset`]) the gaps between
+    /// fields are filled with blanks, so the result is always `calculated_length`
+    /// chars long. A line of `calculated_length` chars parsed with `set_value` and left
+    /// untouched round-trips back to itself through `to_line`, provided any gaps
+    /// between by-offset fields were blank.
+    ///
+    /// # Examples
+    /// ```
+    /// use rbf::record::{AsciiMode, ReadMode, setup::set_up_by_length};
+    ///
+    /// let mut rec = set_up_by_length::<AsciiMode>();
+    /// rec.set_value("AAAAAAAAAABBBBBBBBBBCCCCCCCCCCCCCCCCCCCCDDDDDDDDDD");
+    ///
+    /// assert_eq!(rec.to_line(), "AAAAAAAAAABBBBBBBBBBCCCCCCCCCCCCCCCCCCCCDDDDDDDDDD");
+    ///
+    /// rec[0].set_value("Z");
+    /// assert_eq!(rec.to_line(), "Z         BBBBBBBBBBCCCCCCCCCCCCCCCCCCCCDDDDDDDDDD");
+    /// ```
+    pub fn to_line(&self) -> String {
+        let mut chars: Vec<char> = vec![' '; self.calculated_length];
+
+        for f in &self.flist {
+            let padded = pad(&f.raw_value, f.length, f.ftype.pad_char, &f.ftype.justify);
+
+            for (i, c) in padded.chars().enumerate() {
+                let pos = f.lower_offset + i;
+                if pos < chars.len() {
+                    chars[pos] = c;
+                }
+            }
+        }
+
+        chars.into_iter().collect()
+    }
+
+    /// Writes [`Record::to_line`]'s output to `writer`, followed by a newline.
+    ///
+    /// # Examples
+    /// ```
+    /// use rbf::record::{AsciiMode, ReadMode, setup::set_up_by_length};
+    ///
+    /// let mut rec = set_up_by_length::<AsciiMode>();
+    /// rec.set_value("AAAAAAAAAABBBBBBBBBBCCCCCCCCCCCCCCCCCCCCDDDDDDDDDD");
+    ///
+    /// let mut out: Vec<u8> = Vec::new();
+    /// rec.write_to(&mut out).unwrap();
+    /// assert_eq!(out, b"AAAAAAAAAABBBBBBBBBBCCCCCCCCCCCCCCCCCCCCDDDDDDDDDD\n");
+    /// ```
+    pub fn write_to<W: ::std::io::Write>(&self, writer: &mut W) -> Result<(), RbfError> {
+        writeln!(writer, "{}", self.to_line())?;
+        Ok(())
+    }
+
+    /// Validates every field in one pass, instead of stopping at the first failure
+    /// like [`Field::is_pattern_matched`] alone would. Returns one [`FieldViolation`]
+    /// per offending field, in field order; an empty vector means the record is
+    /// clean.
+    ///
+    /// # Examples
+    /// ```
+    /// use rbf::record::{AsciiMode, ReadMode, setup::set_up_by_length};
+    ///
+    /// let mut rec = set_up_by_length::<AsciiMode>();
+    /// rec.set_value("AAAAAAAAAABBBBBBBBBBCCCCCCCCCCCCCCCCCCCCDDDDDDDDDD");
+    ///
+    /// assert!(rec.validate().is_empty());
+    /// ```
+    pub fn validate(&self) -> Vec<FieldViolation> {
+        self.flist
+            .iter()
+            .filter_map(|f| {
+                let reason = if !f.optional && f.value().is_empty() {
+                    Some(ViolationReason::MissingRequired)
+                } else if !f.is_pattern_matched() {
+                    Some(ViolationReason::PatternMismatch {
+                        pattern: f.ftype.pattern.as_str().to_string(),
+                    })
+                } else {
+                    None
+                };
+
+                reason.map(|reason| FieldViolation {
+                    id: f.id.clone(),
+                    name: f.name.clone(),
+                    raw_value: f.raw_value.clone(),
+                    reason,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Implementation detail of [`Record::deserialize`]: a `serde` `Deserializer` over a
+/// record's fields, grouped by name in first-occurrence order so serde can walk them
+/// as a map keyed by field name, analogous to csv's `deserialize_byte_record`.
+struct RecordDeserializer<'a> {
+    groups: Vec<(&'a str, Vec<&'a str>)>,
+}
+
+impl<'a> RecordDeserializer<'a> {
+    fn new(flist: &'a [Field]) -> Self {
+        let mut groups: Vec<(&'a str, Vec<&'a str>)> = Vec::new();
+
+        for f in flist {
+            match groups.iter_mut().find(|(name, _)| *name == f.name) {
+                Some((_, values)) => values.push(f.value().as_str()),
+                None => groups.push((f.name.as_str(), vec![f.value().as_str()])),
+            }
+        }
+
+        RecordDeserializer { groups }
+    }
+}
+
+impl<'de, 'a> de::Deserializer<'de> for RecordDeserializer<'a> {
+    type Error = RbfError;
+
+    fn deserialize_any<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, RbfError> {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_map<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, RbfError> {
+        visitor.visit_map(FieldMapAccess {
+            groups: self.groups.into_iter(),
+            value: None,
+        })
+    }
+
+    fn deserialize_struct<V: de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, RbfError> {
+        self.deserialize_map(visitor)
+    }
 
-            // if the same field name is found in the record, matches any
-            result &= fields.iter().any(|x| x.is_filter_matched(f));
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct enum identifier ignored_any
+    }
+}
+
+/// `MapAccess` counterpart of [`RecordDeserializer`]: yields one entry per distinct
+/// field name, deferring to [`FieldValueDeserializer`] for the value so a duplicated
+/// name can be deserialized either as a scalar (single occurrence) or a sequence.
+struct FieldMapAccess<'a> {
+    groups: ::std::vec::IntoIter<(&'a str, Vec<&'a str>)>,
+    value: Option<Vec<&'a str>>,
+}
+
+impl<'de, 'a> de::MapAccess<'de> for FieldMapAccess<'a> {
+    type Error = RbfError;
+
+    fn next_key_seed<K: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, RbfError> {
+        match self.groups.next() {
+            Some((name, values)) => {
+                self.value = Some(values);
+                seed.deserialize(de::IntoDeserializer::into_deserializer(name))
+                    .map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: de::DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, RbfError> {
+        let values = self
+            .value
+            .take()
+            .expect("next_value_seed called before next_key_seed");
+        seed.deserialize(FieldValueDeserializer { values })
+    }
+}
+
+/// Implementation detail of [`Record::deserialize`]: deserializes one field's
+/// trimmed value(s) into a scalar (`i64`, `f64`, `bool`, `String`, `Option<_>`), or
+/// as a sequence when the field name was declared more than once.
+struct FieldValueDeserializer<'a> {
+    values: Vec<&'a str>,
+}
+
+impl<'a> FieldValueDeserializer<'a> {
+    fn single(&self) -> Result<&'a str, RbfError> {
+        match self.values.as_slice() {
+            [v] => Ok(*v),
+            other => Err(RbfError::Deserialization(format!(
+                "expected a single value for this field, found {}",
+                other.len()
+            ))),
+        }
+    }
+}
+
+impl<'de, 'a> de::IntoDeserializer<'de, RbfError> for FieldValueDeserializer<'a> {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+macro_rules! deserialize_parsed {
+    ($method:ident, $visit:ident, $ty:ty) => {
+        fn $method<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, RbfError> {
+            let raw = self.single()?;
+            let parsed: $ty = raw.trim().parse().map_err(|e| {
+                RbfError::Deserialization(format!(
+                    "cannot parse \"{}\" as {}: {}",
+                    raw,
+                    stringify!($ty),
+                    e
+                ))
+            })?;
+            visitor.$visit(parsed)
+        }
+    };
+}
+
+impl<'de, 'a> de::Deserializer<'de> for FieldValueDeserializer<'a> {
+    type Error = RbfError;
+
+    fn deserialize_any<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, RbfError> {
+        if self.values.len() == 1 {
+            visitor.visit_str(self.values[0])
+        } else {
+            self.deserialize_seq(visitor)
+        }
+    }
+
+    deserialize_parsed!(deserialize_i64, visit_i64, i64);
+    deserialize_parsed!(deserialize_f64, visit_f64, f64);
+    deserialize_parsed!(deserialize_bool, visit_bool, bool);
+
+    fn deserialize_str<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, RbfError> {
+        visitor.visit_str(self.single()?)
+    }
+
+    fn deserialize_string<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, RbfError> {
+        visitor.visit_string(self.single()?.to_string())
+    }
+
+    fn deserialize_option<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, RbfError> {
+        match self.values.as_slice() {
+            [v] if v.is_empty() => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_seq<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, RbfError> {
+        visitor.visit_seq(de::value::SeqDeserializer::new(
+            self.values
+                .into_iter()
+                .map(|v| FieldValueDeserializer { values: vec![v] }),
+        ))
+    }
+
+    serde::forward_to_deserialize_any! {
+        i8 i16 i32 i128 u8 u16 u32 u64 u128 f32 char bytes byte_buf
+        unit unit_struct newtype_struct tuple tuple_struct map struct
+        enum identifier ignored_any
+    }
+}
+
+/// Why a field failed [`Record::validate`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum ViolationReason {
+    /// the value doesn't match the field type's declared pattern
+    PatternMismatch { pattern: String },
+    /// the value's length doesn't match the field's declared length
+    LengthMismatch { expected: usize, actual: usize },
+    /// a non-optional field has no value (see `Field::is_present`)
+    MissingRequired,
+}
+
+/// One field's validation failure, as reported by [`Record::validate`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldViolation {
+    pub id: String,
+    pub name: String,
+    pub raw_value: String,
+    pub reason: ViolationReason,
+}
+
+impl fmt::Display for FieldViolation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.reason {
+            ViolationReason::PatternMismatch { pattern } => write!(
+                f,
+                "- {}: value '{}' does not match {}",
+                self.name, self.raw_value, pattern
+            ),
+            ViolationReason::LengthMismatch { expected, actual } => write!(
+                f,
+                "- {}: value '{}' has length {}, expected {}",
+                self.name, self.raw_value, actual, expected
+            ),
+            ViolationReason::MissingRequired => write!(
+                f,
+                "- {}: value '{}' is required but blank",
+                self.name, self.raw_value
+            ),
         }
-        result
     }
 }
 
@@ -1,10 +1,15 @@
+use std::cmp::Ordering;
 use std::fmt;
 
-use crate::types::datetime::{DateType, TimeType};
+use crate::error::RbfError;
+use crate::types::bigdecimal::BigDecimalType;
+use crate::types::datetime::{DateTimeType, DateType, TimeType};
 use crate::types::decimal::DecimalType;
 use crate::types::int::SignedIntegerType;
+use crate::types::number::NumberType;
 use crate::types::string::StringType;
 use crate::types::uint::UnsignedIntegerType;
+use crate::types::value::TypedValue;
 
 #[macro_export]
 macro_rules! get_name {
@@ -32,23 +37,124 @@ pub trait BaseType {
     fn get_name(&self) -> &'static str;
     fn set_format(&mut self, fmt: &str);
     fn get_format(&self) -> &str;
-    fn eq(&self, lhs: &str, rhs: &str) -> bool;
-    fn lt(&self, lhs: &str, rhs: &str) -> bool;
-    fn gt(&self, lhs: &str, rhs: &str) -> bool;
+
+    /// Parses a raw field value into its typed representation, which `compare` can
+    /// then order against other parsed values any number of times without re-parsing.
+    fn parse(&self, raw: &str) -> Result<TypedValue, RbfError>;
+
+    /// Orders two already-parsed values of this type.
+    ///
+    /// # Panics
+    /// If `lhs`/`rhs` aren't the `TypedValue` variant this type's `parse` produces:
+    /// callers only ever compare values parsed by the same `BaseType`.
+    fn compare(&self, lhs: &TypedValue, rhs: &TypedValue) -> Ordering;
+
+    /// Thin wrappers over `parse`/`compare`, kept for source compatibility: each call
+    /// parses both operands. A value that fails to parse never matches: a single bad
+    /// field shouldn't abort the run.
+    fn eq(&self, lhs: &str, rhs: &str) -> bool {
+        match (self.parse(lhs), self.parse(rhs)) {
+            (Ok(l), Ok(r)) => self.compare(&l, &r) == Ordering::Equal,
+            _ => false,
+        }
+    }
+
+    fn lt(&self, lhs: &str, rhs: &str) -> bool {
+        match (self.parse(lhs), self.parse(rhs)) {
+            (Ok(l), Ok(r)) => self.compare(&l, &r) == Ordering::Less,
+            _ => false,
+        }
+    }
+
+    fn gt(&self, lhs: &str, rhs: &str) -> bool {
+        match (self.parse(lhs), self.parse(rhs)) {
+            (Ok(l), Ok(r)) => self.compare(&l, &r) == Ordering::Greater,
+            _ => false,
+        }
+    }
+
+    /// `lhs <= rhs`. Not `!gt(lhs, rhs)`: negating `gt` would turn an unparseable
+    /// `lhs`/`rhs` (which `gt` reports as `false`) into a spurious match.
+    fn le(&self, lhs: &str, rhs: &str) -> bool {
+        match (self.parse(lhs), self.parse(rhs)) {
+            (Ok(l), Ok(r)) => self.compare(&l, &r) != Ordering::Greater,
+            _ => false,
+        }
+    }
+
+    /// `lhs >= rhs`. Not `!lt(lhs, rhs)`, for the same reason `le` isn't `!gt`.
+    fn ge(&self, lhs: &str, rhs: &str) -> bool {
+        match (self.parse(lhs), self.parse(rhs)) {
+            (Ok(l), Ok(r)) => self.compare(&l, &r) != Ordering::Less,
+            _ => false,
+        }
+    }
+
+    /// Converts a raw field value to its JSON representation, via `parse`. Strings
+    /// map to JSON strings, int/uint to JSON numbers, decimals to a JSON number (or
+    /// a string when the round trip through `f64` would lose precision), and
+    /// dates/times/datetimes to ISO-8601 strings.
+    fn to_json(&self, raw: &str) -> Result<::serde_json::Value, RbfError> {
+        let value = self.parse(raw)?;
+
+        Ok(match value {
+            TypedValue::Str(s) => ::serde_json::Value::String(s),
+            TypedValue::Int(i) => ::serde_json::Value::from(i),
+            TypedValue::Uint(u) => ::serde_json::Value::from(u),
+            TypedValue::Decimal(d) => {
+                // nothing is lost when `f64`'s shortest rendering spells the same digits
+                // as `raw`, sign, leading zeros and trailing fractional zeros set aside
+                let t = raw.trim().trim_start_matches('+');
+                let t = if t.contains('.') { t.trim_end_matches('0').trim_end_matches('.') } else { t };
+                let lossless = d.to_string().trim_start_matches('-').trim_start_matches('0')
+                    == t.trim_start_matches('-').trim_start_matches('0');
+                match ::serde_json::Number::from_f64(d) {
+                    Some(n) if lossless => ::serde_json::Value::Number(n),
+                    _ => ::serde_json::Value::String(raw.trim().to_string()),
+                }
+            }
+            TypedValue::Date(d) => ::serde_json::Value::String(d.format("%Y-%m-%d").to_string()),
+            TypedValue::Time(t) => ::serde_json::Value::String(t.format("%H:%M:%S").to_string()),
+            TypedValue::DateTime(dt) => ::serde_json::Value::String(dt.to_rfc3339()),
+            TypedValue::BigDecimal(unscaled, scale) => ::serde_json::Value::String(
+                crate::types::bigdecimal::to_decimal_string(&unscaled, scale),
+            ),
+        })
+    }
 }
 
-/// Convenient conversion from a string ref.
-impl<'a> From<&'a str> for Box<BaseType> {
-    fn from(original: &'a str) -> Box<BaseType> {
-        match original {
+/// Fallible conversion from a string ref: the non-panicking counterpart of
+/// `From<&str> for Box<BaseType>`, for callers that want to report an unknown type
+/// name (e.g. from user-supplied layout XML) rather than abort.
+impl<'a> ::std::convert::TryFrom<&'a str> for Box<BaseType> {
+    type Error = RbfError;
+
+    fn try_from(original: &'a str) -> Result<Box<BaseType>, RbfError> {
+        let base_type: Box<BaseType> = match original {
             "string" => Box::new(StringType::new("")),
             "decimal" => Box::new(DecimalType::new("")),
+            "number" => Box::new(NumberType::new("")),
             "int" => Box::new(SignedIntegerType::new("")),
             "uint" => Box::new(UnsignedIntegerType::new("")),
             "date" => Box::new(DateType::new("")),
             "time" => Box::new(TimeType::new("")),
-            unknown_type @ _ => panic!("<{}> is not allowed as a field type", unknown_type),
-        }
+            "datetime" => Box::new(DateTimeType::new("")),
+            "bigdecimal" => Box::new(BigDecimalType::new("")),
+            unknown_type @ _ => return Err(RbfError::UnknownType(unknown_type.to_string())),
+        };
+        Ok(base_type)
+    }
+}
+
+/// Convenient conversion from a string ref.
+///
+/// # Panics
+/// If `original` isn't one of the known type names. Prefer `TryFrom` when a
+/// non-panicking conversion is needed.
+impl<'a> From<&'a str> for Box<BaseType> {
+    fn from(original: &'a str) -> Box<BaseType> {
+        use std::convert::TryFrom;
+        Box::<BaseType>::try_from(original).expect("unknown field type")
     }
 }
 
@@ -66,9 +172,9 @@ mod tests {
     #[test]
     fn comparison() {
         // test all types
-        let target: Vec<&str> = "FOO;3.14;-100;100;20170101;120000".split(';').collect();
-        let behind: Vec<&str> = "FOM;3.13;-101;99;20161231;115959".split(';').collect();
-        let over: Vec<&str> = "FOP;3.15;-99;101;20170102;120001".split(';').collect();
+        let target: Vec<&str> = "FOO;3.14;-100;100;20170101;120000;100".split(';').collect();
+        let behind: Vec<&str> = "FOM;3.13;-101;99;20161231;115959;99".split(';').collect();
+        let over: Vec<&str> = "FOP;3.15;-99;101;20170102;120001;101".split(';').collect();
 
         // string, etc
         let st = Box::<BaseType>::from("string");
@@ -102,5 +208,32 @@ mod tests {
         assert!(tt.eq(target[5], target[5]));
         assert!(tt.lt(behind[5], target[5]));
         assert!(tt.gt(over[5], target[5]));
+
+        let nt = Box::<BaseType>::from("number");
+        assert!(nt.eq(target[6], target[6]));
+        assert!(nt.lt(behind[6], target[6]));
+        assert!(nt.gt(over[6], target[6]));
+
+        let mut ct = Box::<BaseType>::from("datetime");
+        ct.set_format("%Y-%m-%dT%H:%M:%S%z");
+        assert!(ct.eq("2017-01-01T12:00:00+0000", "2017-01-01T12:00:00+0000"));
+        assert!(ct.lt("2016-12-31T23:59:59+0000", "2017-01-01T12:00:00+0000"));
+        assert!(ct.gt("2017-01-02T12:00:01+0000", "2017-01-01T12:00:00+0000"));
+        // same instant expressed with a different offset still compares equal
+        assert!(ct.eq("2017-01-01T14:00:00+0200", "2017-01-01T12:00:00+0000"));
+    }
+
+    #[test]
+    fn bigdecimal_comparison() {
+        let bd = Box::<BaseType>::from("bigdecimal");
+        assert!(bd.eq("123.450", "123.45"));
+        assert!(bd.lt("99.99999999999999999999999999", "100"));
+        assert!(bd.gt("100.000000000000000000000001", "100"));
+        assert!(bd.eq("-0.5", "-0.50"));
+
+        let mut comma = Box::<BaseType>::from("bigdecimal");
+        comma.set_format("9.999,99");
+        assert!(comma.eq("1.234,56", "1.234,56"));
+        assert!(comma.lt("1.234,56", "2.000,00"));
     }
 }
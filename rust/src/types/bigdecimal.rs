@@ -0,0 +1,129 @@
+use std::cmp::Ordering;
+
+use num_bigint::BigInt;
+
+use crate::error::RbfError;
+use crate::types::base::BaseType;
+use crate::types::root::RootType;
+use crate::types::value::TypedValue;
+
+/// Marker type for `RootType`: its `format` field holds a separator spec instead of
+/// a chrono pattern, e.g. `"9.999,99"` to mean '.' is the thousands separator and
+/// ',' is the decimal one. An empty format defaults to a plain '.' decimal separator
+/// with no grouping.
+pub struct BigDecimalMarker;
+
+/// An arbitrary-precision decimal, parsed into an unscaled `BigInt` plus a scale
+/// rather than an `f64`, so that long fixed-point amounts (e.g. monetary fields)
+/// compare exactly instead of silently losing precision.
+pub type BigDecimalType = RootType<BigDecimalMarker>;
+
+/// Extracts `(decimal separator, thousands separator)` from a `set_format` spec.
+fn separators(format: &str) -> (char, Option<char>) {
+    let non_digits: Vec<char> = format.chars().filter(|c| !c.is_ascii_digit()).collect();
+
+    match non_digits.len() {
+        0 => ('.', None),
+        1 => (non_digits[0], None),
+        _ => (non_digits[non_digits.len() - 1], Some(non_digits[0])),
+    }
+}
+
+/// Renders an unscaled big integer + scale back to its plain decimal string, e.g.
+/// `(12345, 2)` -> `"123.45"`. Used to hand exact values over to JSON output.
+pub fn to_decimal_string(unscaled: &BigInt, scale: i32) -> String {
+    let negative = unscaled.sign() == num_bigint::Sign::Minus;
+    let digits = unscaled.magnitude().to_str_radix(10);
+
+    if scale <= 0 {
+        return format!("{}{}", if negative { "-" } else { "" }, digits);
+    }
+
+    let scale = scale as usize;
+    let digits = if digits.len() <= scale {
+        format!("{:0>width$}", digits, width = scale + 1)
+    } else {
+        digits
+    };
+
+    let split = digits.len() - scale;
+    format!(
+        "{}{}.{}",
+        if negative { "-" } else { "" },
+        &digits[..split],
+        &digits[split..]
+    )
+}
+
+/// 10 raised to a non-negative power, as a `BigInt`.
+fn ten_pow(exp: i32) -> BigInt {
+    let mut result = BigInt::from(1);
+    for _ in 0..exp {
+        result *= 10;
+    }
+    result
+}
+
+impl BaseType for BigDecimalType {
+    // Returns name of the type
+    get_name!(self, "bigdecimal");
+
+    /// Sets the format using the RootType method
+    set_format!(self, fmt);
+
+    /// Gets for format string.
+    get_format!(self);
+
+    fn parse(&self, raw: &str) -> Result<TypedValue, RbfError> {
+        let (dec_sep, thousands_sep) = separators(self.get_format());
+        let trimmed = raw.trim();
+
+        let (negative, unsigned) = match trimmed.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, trimmed.strip_prefix('+').unwrap_or(trimmed)),
+        };
+
+        let mut cleaned = String::with_capacity(unsigned.len());
+        for c in unsigned.chars() {
+            if Some(c) == thousands_sep {
+                continue;
+            }
+            cleaned.push(c);
+        }
+
+        let (int_part, frac_part) = match cleaned.find(dec_sep) {
+            Some(pos) => (&cleaned[..pos], &cleaned[pos + 1..]),
+            None => (cleaned.as_str(), ""),
+        };
+
+        let is_valid = !(int_part.is_empty() && frac_part.is_empty())
+            && int_part.chars().all(|c| c.is_ascii_digit())
+            && frac_part.chars().all(|c| c.is_ascii_digit());
+
+        if !is_valid {
+            return Err(RbfError::InvalidFieldValue(raw.to_string()));
+        }
+
+        let scale = frac_part.len() as i32;
+        let digits = format!("{}{}", int_part, frac_part);
+        let mut unscaled =
+            BigInt::parse_bytes(digits.as_bytes(), 10).expect("digits already validated");
+        if negative {
+            unscaled = -unscaled;
+        }
+
+        Ok(TypedValue::BigDecimal(unscaled, scale))
+    }
+
+    fn compare(&self, lhs: &TypedValue, rhs: &TypedValue) -> Ordering {
+        match (lhs, rhs) {
+            (TypedValue::BigDecimal(lu, ls), TypedValue::BigDecimal(ru, rs)) => {
+                let scale = (*ls).max(*rs);
+                let l = lu * ten_pow(scale - ls);
+                let r = ru * ten_pow(scale - rs);
+                l.cmp(&r)
+            }
+            _ => panic!("BigDecimalType::compare called with non-BigDecimal typed values"),
+        }
+    }
+}
@@ -1,36 +1,33 @@
+use std::cmp::Ordering;
+
 use chrono::prelude::*;
-use std::error::Error;
 
+use crate::error::RbfError;
 use crate::types::base::BaseType;
 use crate::types::root::RootType;
+use crate::types::value::TypedValue;
+
+fn to_date(value: &str, fmt: &str) -> Result<NaiveDate, RbfError> {
+    NaiveDate::parse_from_str(value, fmt).map_err(|_| RbfError::InvalidFieldValue(value.to_string()))
+}
 
-fn to_date(value: &str, fmt: &str) -> NaiveDate {
-    let converted = match NaiveDate::parse_from_str(value, fmt) {
-        Ok(v) => v,
-        Err(e) => panic!(
-            "unable to convert string value {}, error={}",
-            value,
-            e.description()
-        ),
-    };
-    converted
+fn to_time(value: &str, fmt: &str) -> Result<NaiveTime, RbfError> {
+    NaiveTime::parse_from_str(value, fmt).map_err(|_| RbfError::InvalidFieldValue(value.to_string()))
 }
 
-fn to_time(value: &str, fmt: &str) -> NaiveTime {
-    let converted = match NaiveTime::parse_from_str(value, fmt) {
-        Ok(v) => v,
-        Err(e) => panic!(
-            "unable to convert string value {}, error={}",
-            value,
-            e.description()
-        ),
-    };
-    converted
+fn to_datetime(value: &str, fmt: &str) -> Result<DateTime<FixedOffset>, RbfError> {
+    DateTime::parse_from_str(value, fmt)
+        .map_err(|_| RbfError::InvalidFieldValue(value.to_string()))
 }
 
 pub type DateType = RootType<NaiveDate>;
 pub type TimeType = RootType<NaiveTime>;
 
+/// A combined date+time base type with a numeric timezone offset (e.g. `+0200`),
+/// parsed via chrono. Unlike `DateType`/`TimeType` it carries the offset in every
+/// comparison, so `2020-06-01T10:00:00+0000` and `2020-06-01T12:00:00+0200` compare equal.
+pub type DateTimeType = RootType<DateTime<FixedOffset>>;
+
 impl BaseType for DateType {
     // Returns name of the type
     get_name!(self, "date");
@@ -41,19 +38,15 @@ impl BaseType for DateType {
     /// Gets for format string.
     get_format!(self);
 
-    // for strings, not need to call convert() first
-    fn eq(&self, lhs: &str, rhs: &str) -> bool {
-        to_date(lhs, &self.format) == to_date(rhs, &self.format)
+    fn parse(&self, raw: &str) -> Result<TypedValue, RbfError> {
+        to_date(raw, &self.format).map(TypedValue::Date)
     }
 
-    // for strings, not need to call convert() first
-    fn lt(&self, lhs: &str, rhs: &str) -> bool {
-        to_date(lhs, &self.format) < to_date(rhs, &self.format)
-    }
-
-    // for strings, not need to call convert() first
-    fn gt(&self, lhs: &str, rhs: &str) -> bool {
-        to_date(lhs, &self.format) > to_date(rhs, &self.format)
+    fn compare(&self, lhs: &TypedValue, rhs: &TypedValue) -> Ordering {
+        match (lhs, rhs) {
+            (TypedValue::Date(l), TypedValue::Date(r)) => l.cmp(r),
+            _ => panic!("DateType::compare called with non-Date typed values"),
+        }
     }
 }
 
@@ -67,18 +60,36 @@ impl BaseType for TimeType {
     /// Gets for format string.
     get_format!(self);
 
-    // for strings, not need to call convert() first
-    fn eq(&self, lhs: &str, rhs: &str) -> bool {
-        to_time(lhs, &self.format) == to_time(rhs, &self.format)
+    fn parse(&self, raw: &str) -> Result<TypedValue, RbfError> {
+        to_time(raw, &self.format).map(TypedValue::Time)
+    }
+
+    fn compare(&self, lhs: &TypedValue, rhs: &TypedValue) -> Ordering {
+        match (lhs, rhs) {
+            (TypedValue::Time(l), TypedValue::Time(r)) => l.cmp(r),
+            _ => panic!("TimeType::compare called with non-Time typed values"),
+        }
     }
+}
+
+impl BaseType for DateTimeType {
+    // Returns name of the type
+    get_name!(self, "datetime");
+
+    /// Sets the format using the RootType method
+    set_format!(self, fmt);
+
+    /// Gets for format string.
+    get_format!(self);
 
-    // for strings, not need to call convert() first
-    fn lt(&self, lhs: &str, rhs: &str) -> bool {
-        to_time(lhs, &self.format) < to_time(rhs, &self.format)
+    fn parse(&self, raw: &str) -> Result<TypedValue, RbfError> {
+        to_datetime(raw, &self.format).map(TypedValue::DateTime)
     }
 
-    // for strings, not need to call convert() first
-    fn gt(&self, lhs: &str, rhs: &str) -> bool {
-        to_time(lhs, &self.format) > to_time(rhs, &self.format)
+    fn compare(&self, lhs: &TypedValue, rhs: &TypedValue) -> Ordering {
+        match (lhs, rhs) {
+            (TypedValue::DateTime(l), TypedValue::DateTime(r)) => l.cmp(r),
+            _ => panic!("DateTimeType::compare called with non-DateTime typed values"),
+        }
     }
 }
@@ -1,6 +1,9 @@
+use std::cmp::Ordering;
+
+use crate::error::RbfError;
 use crate::types::base::BaseType;
-use crate::types::compare::Compare;
 use crate::types::root::RootType;
+use crate::types::value::TypedValue;
 
 type Decimal = f64;
 pub type DecimalType = RootType<Decimal>;
@@ -15,15 +18,26 @@ impl BaseType for DecimalType {
     /// Gets for format string.
     get_format!(self);
 
-    fn eq(&self, lhs: &str, rhs: &str) -> bool {
-        Compare::<Decimal>::eq(lhs, rhs)
-    }
+    fn parse(&self, raw: &str) -> Result<TypedValue, RbfError> {
+        let value: Decimal = raw
+            .parse()
+            .map_err(|_| RbfError::InvalidFieldValue(raw.to_string()))?;
+
+        // `"NaN"` parses fine as `f64` but isn't orderable; treat it as invalid
+        // input rather than letting `compare` panic on it later
+        if value.is_nan() {
+            return Err(RbfError::InvalidFieldValue(raw.to_string()));
+        }
 
-    fn lt(&self, lhs: &str, rhs: &str) -> bool {
-        Compare::<Decimal>::lt(lhs, rhs)
+        Ok(TypedValue::Decimal(value))
     }
 
-    fn gt(&self, lhs: &str, rhs: &str) -> bool {
-        Compare::<Decimal>::gt(lhs, rhs)
+    fn compare(&self, lhs: &TypedValue, rhs: &TypedValue) -> Ordering {
+        match (lhs, rhs) {
+            (TypedValue::Decimal(l), TypedValue::Decimal(r)) => {
+                l.partial_cmp(r).expect("NaN is not orderable: parse() rejects it")
+            }
+            _ => panic!("DecimalType::compare called with non-Decimal typed values"),
+        }
     }
 }
@@ -1,13 +1,17 @@
 //! Represents a basic type used by fields. Each field can be associated with a standard type,
 //! which defines the type data it holds.
 //!
-//! 5 different types can be used, but it can be easily extended if desired:
+//! 9 different types can be used, but it can be easily extended if desired:
 //!
 //!  * `string`
 //!  * `integer`
 //!  * `decimal`
+//!  * `number`
+//!  * `uint`
 //!  * `date`
 //!  * `time`
+//!  * `datetime`
+//!  * `bigdecimal`: arbitrary-precision decimal, backed by an unscaled `num_bigint::BigInt`
 //!
 //! # Examples
 //! ```rust
@@ -20,10 +24,20 @@
 //! ```
 
 use regex::Regex;
+use std::convert::TryFrom;
 use std::fmt;
 
+use crate::error::RbfError;
 use crate::types::base::BaseType;
 
+/// Which side of a value gets padded out to a field's declared length when writing
+/// a record back out.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Justify {
+    Left,
+    Right,
+}
+
 pub struct FieldType {
     /// Nickname for the field type
     pub id: String,
@@ -33,6 +47,10 @@ pub struct FieldType {
     pub base_type: Box<BaseType>,
     /// Optional pattern which describes field format
     pub pattern: Regex,
+    /// Character used to pad a value out to the field's declared length when writing
+    pub pad_char: char,
+    /// Which side gets the padding when writing
+    pub justify: Justify,
 }
 
 impl FieldType {
@@ -43,20 +61,29 @@ impl FieldType {
     ///
     /// * `id` - nickname for the field type
     /// * `type_as_string`: base underlying type
-    ///    
+    ///
+    /// # Panics
+    /// If `id` is empty or `type_as_string` is not a known type. Prefer
+    /// [`FieldType::try_new`] when a non-panicking conversion is needed.
     pub fn new(id: &str, type_as_string: &str) -> FieldType {
-        // first test arguments: non-sense to deal with empty data
+        FieldType::try_new(id, type_as_string).expect("unable to create FieldType")
+    }
+
+    /// Fallible counterpart of [`FieldType::new`]: reports an empty id or an unknown
+    /// base type instead of panicking, for callers parsing user-supplied layout XML.
+    pub fn try_new(id: &str, type_as_string: &str) -> Result<FieldType, RbfError> {
         if id.is_empty() {
-            panic!("cannot create a FieldType with empty id!");
+            return Err(RbfError::EmptyId);
         }
 
-        // according to string type, create corresponding type
-        FieldType {
+        Ok(FieldType {
             id: id.to_string(),
             type_as_string: type_as_string.to_string(),
-            base_type: Box::<BaseType>::from(type_as_string),
+            base_type: Box::<BaseType>::try_from(type_as_string)?,
             pattern: Regex::new("").unwrap(),
-        }
+            pad_char: ' ',
+            justify: Justify::Left,
+        })
     }
 
     /// Sets the regex pattern for the field type.
@@ -65,8 +92,35 @@ impl FieldType {
     ///
     /// * `pattern` - string regex
     ///
+    /// # Panics
+    /// If `pattern` doesn't compile as a regex. Prefer [`FieldType::try_set_pattern`]
+    /// when a non-panicking conversion is needed.
     pub fn set_pattern(&mut self, pattern: &str) {
-        self.pattern = Regex::new(pattern).unwrap();
+        self.try_set_pattern(pattern).expect("invalid field type pattern");
+    }
+
+    /// Fallible counterpart of [`FieldType::set_pattern`].
+    pub fn try_set_pattern(&mut self, pattern: &str) -> Result<(), RbfError> {
+        self.pattern = Regex::new(pattern)?;
+        Ok(())
+    }
+
+    /// Sets the padding character and justification used when writing a value of
+    /// this type back out to a fixed-width record. Defaults to left-justified,
+    /// padded with blanks.
+    ///
+    /// # Examples
+    /// ```
+    /// use rbf::types::fieldtype::{FieldType, Justify};
+    ///
+    /// let mut ft = FieldType::new("I", "int");
+    /// ft.set_padding('0', Justify::Right);
+    /// assert_eq!(ft.pad_char, '0');
+    /// assert_eq!(ft.justify, Justify::Right);
+    /// ```
+    pub fn set_padding(&mut self, pad_char: char, justify: Justify) {
+        self.pad_char = pad_char;
+        self.justify = justify;
     }
 }
 
@@ -1,6 +1,9 @@
+use std::cmp::Ordering;
+
+use crate::error::RbfError;
 use crate::types::base::BaseType;
-use crate::types::compare::Compare;
 use crate::types::root::RootType;
+use crate::types::value::TypedValue;
 
 type SignedInteger = i64;
 pub type SignedIntegerType = RootType<SignedInteger>;
@@ -15,15 +18,16 @@ impl BaseType for SignedIntegerType {
     /// Gets for format string.
     get_format!(self);
 
-    fn eq(&self, lhs: &str, rhs: &str) -> bool {
-        Compare::<SignedInteger>::eq(lhs, rhs)
-    }
-
-    fn lt(&self, lhs: &str, rhs: &str) -> bool {
-        Compare::<SignedInteger>::lt(lhs, rhs)
+    fn parse(&self, raw: &str) -> Result<TypedValue, RbfError> {
+        raw.parse::<SignedInteger>()
+            .map(TypedValue::Int)
+            .map_err(|_| RbfError::InvalidFieldValue(raw.to_string()))
     }
 
-    fn gt(&self, lhs: &str, rhs: &str) -> bool {
-        Compare::<SignedInteger>::gt(lhs, rhs)
+    fn compare(&self, lhs: &TypedValue, rhs: &TypedValue) -> Ordering {
+        match (lhs, rhs) {
+            (TypedValue::Int(l), TypedValue::Int(r)) => l.cmp(r),
+            _ => panic!("SignedIntegerType::compare called with non-Int typed values"),
+        }
     }
 }
@@ -0,0 +1,46 @@
+use std::cmp::Ordering;
+
+use crate::error::RbfError;
+use crate::types::base::BaseType;
+use crate::types::root::RootType;
+use crate::types::value::TypedValue;
+
+type Number = f64;
+
+/// A generic numeric base type, for fields that should compare as plain numbers
+/// (`AMOUNT > 100`) without the fixed-scale/formatting semantics `DecimalType` implies.
+pub type NumberType = RootType<Number>;
+
+impl BaseType for NumberType {
+    // Returns name of the type
+    get_name!(self, "number");
+
+    /// Sets the format using the RootType method
+    set_format!(self, fmt);
+
+    /// Gets for format string.
+    get_format!(self);
+
+    fn parse(&self, raw: &str) -> Result<TypedValue, RbfError> {
+        let value: Number = raw
+            .parse()
+            .map_err(|_| RbfError::InvalidFieldValue(raw.to_string()))?;
+
+        // `"NaN"` parses fine as `f64` but isn't orderable; treat it as invalid
+        // input rather than letting `compare` panic on it later
+        if value.is_nan() {
+            return Err(RbfError::InvalidFieldValue(raw.to_string()));
+        }
+
+        Ok(TypedValue::Decimal(value))
+    }
+
+    fn compare(&self, lhs: &TypedValue, rhs: &TypedValue) -> Ordering {
+        match (lhs, rhs) {
+            (TypedValue::Decimal(l), TypedValue::Decimal(r)) => {
+                l.partial_cmp(r).expect("NaN is not orderable: parse() rejects it")
+            }
+            _ => panic!("NumberType::compare called with non-Decimal typed values"),
+        }
+    }
+}
@@ -1,5 +1,9 @@
+use std::cmp::Ordering;
+
+use crate::error::RbfError;
 use crate::types::base::BaseType;
 use crate::types::root::RootType;
+use crate::types::value::TypedValue;
 
 pub type StringType = RootType<String>;
 
@@ -13,18 +17,15 @@ impl BaseType for StringType {
     /// Gets for format string.
     get_format!(self);
 
-    // for strings, not need to call convert() first
-    fn eq(&self, lhs: &str, rhs: &str) -> bool {
-        lhs == rhs
-    }
-
-    // for strings, not need to call convert() first
-    fn lt(&self, lhs: &str, rhs: &str) -> bool {
-        lhs < rhs
+    // strings never fail to parse: the raw value is the typed value
+    fn parse(&self, raw: &str) -> Result<TypedValue, RbfError> {
+        Ok(TypedValue::Str(raw.to_string()))
     }
 
-    // for strings, not need to call convert() first
-    fn gt(&self, lhs: &str, rhs: &str) -> bool {
-        lhs > rhs
+    fn compare(&self, lhs: &TypedValue, rhs: &TypedValue) -> Ordering {
+        match (lhs, rhs) {
+            (TypedValue::Str(l), TypedValue::Str(r)) => l.cmp(r),
+            _ => panic!("StringType::compare called with non-Str typed values"),
+        }
     }
 }
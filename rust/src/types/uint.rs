@@ -1,6 +1,9 @@
+use std::cmp::Ordering;
+
+use crate::error::RbfError;
 use crate::types::base::BaseType;
-use crate::types::compare::Compare;
 use crate::types::root::RootType;
+use crate::types::value::TypedValue;
 
 type UnsignedInteger = u64;
 pub type UnsignedIntegerType = RootType<UnsignedInteger>;
@@ -15,15 +18,16 @@ impl BaseType for UnsignedIntegerType {
     /// Gets for format string.
     get_format!(self);
 
-    fn eq(&self, lhs: &str, rhs: &str) -> bool {
-        Compare::<UnsignedInteger>::eq(lhs, rhs)
-    }
-
-    fn lt(&self, lhs: &str, rhs: &str) -> bool {
-        Compare::<UnsignedInteger>::lt(lhs, rhs)
+    fn parse(&self, raw: &str) -> Result<TypedValue, RbfError> {
+        raw.parse::<UnsignedInteger>()
+            .map(TypedValue::Uint)
+            .map_err(|_| RbfError::InvalidFieldValue(raw.to_string()))
     }
 
-    fn gt(&self, lhs: &str, rhs: &str) -> bool {
-        Compare::<UnsignedInteger>::gt(lhs, rhs)
+    fn compare(&self, lhs: &TypedValue, rhs: &TypedValue) -> Ordering {
+        match (lhs, rhs) {
+            (TypedValue::Uint(l), TypedValue::Uint(r)) => l.cmp(r),
+            _ => panic!("UnsignedIntegerType::compare called with non-Uint typed values"),
+        }
     }
 }
@@ -0,0 +1,24 @@
+//! A field value that has already been parsed according to its declared `BaseType`.
+//!
+//! [`BaseType::parse`](crate::types::base::BaseType::parse) produces one of these from a
+//! raw string; a value kept in this form can be compared through `BaseType::compare` any
+//! number of times without re-parsing. The string-based `eq`/`lt`/`gt`/`le`/`ge`
+//! wrappers of `BaseType` parse both operands on every call.
+
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveTime};
+use num_bigint::BigInt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TypedValue {
+    Str(String),
+    Int(i64),
+    Uint(u64),
+    Decimal(f64),
+    Date(NaiveDate),
+    Time(NaiveTime),
+    DateTime(DateTime<FixedOffset>),
+    /// An arbitrary-precision decimal, as an unscaled integer plus the count of
+    /// digits after the decimal point, e.g. `(12345, 2)` is `123.45`. Produced by
+    /// [`BigDecimalType`](crate::types::bigdecimal::BigDecimalType).
+    BigDecimal(BigInt, i32),
+}